@@ -0,0 +1,145 @@
+use nom::{
+    branch::alt,
+    combinator::{map, opt},
+    sequence::preceded,
+    IResult,
+};
+
+use super::{JumpScope, Statement};
+use crate::{
+    context::MutableContext,
+    control_flow::ControlFlow,
+    expression::{parse_expression, Expression},
+    function::Functions,
+    parse_utilities::{blank, keyword},
+    value::{NumericValue, UNINITIALIZED_VALUE},
+};
+
+/// `break`, `continue`, `next`, and `nextfile` unconditionally produce their control flow
+struct Jump {
+    flow: ControlFlow,
+}
+
+impl Statement for Jump {
+    fn evaluate(&self, _functions: &Functions, _context: &mut MutableContext) -> ControlFlow {
+        self.flow.clone()
+    }
+
+    fn check_jumps(&self, scope: JumpScope) -> Result<(), &'static str> {
+        match self.flow {
+            ControlFlow::Break | ControlFlow::Continue if !scope.in_loop => {
+                Err("break and continue can only be used inside of a loop")
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+struct Exit {
+    status: Option<Box<dyn Expression>>,
+}
+
+impl Statement for Exit {
+    fn evaluate(&self, functions: &Functions, context: &mut MutableContext) -> ControlFlow {
+        match &self.status {
+            Some(expression) => {
+                let status = match expression.evaluate(functions, context).coerce_to_numeric() {
+                    NumericValue::Integer(i) => i as i32,
+                    NumericValue::Float(f) => f as i32,
+                };
+                ControlFlow::Exit(Some(status))
+            }
+            None => ControlFlow::Exit(None),
+        }
+    }
+}
+
+struct Return {
+    value: Option<Box<dyn Expression>>,
+}
+
+impl Statement for Return {
+    fn evaluate(&self, functions: &Functions, context: &mut MutableContext) -> ControlFlow {
+        match &self.value {
+            Some(expression) => ControlFlow::Return(expression.evaluate(functions, context)),
+            None => ControlFlow::Return(UNINITIALIZED_VALUE.clone()),
+        }
+    }
+
+    fn check_jumps(&self, scope: JumpScope) -> Result<(), &'static str> {
+        if scope.in_function {
+            Ok(())
+        } else {
+            Err("return can only be used inside of a function")
+        }
+    }
+}
+
+fn parse_jump_statement(input: &str) -> IResult<&str, Box<dyn Statement>> {
+    map(
+        alt((
+            map(keyword("break"), |_| ControlFlow::Break),
+            map(keyword("continue"), |_| ControlFlow::Continue),
+            map(keyword("nextfile"), |_| ControlFlow::NextFile),
+            map(keyword("next"), |_| ControlFlow::Next),
+        )),
+        |flow| Box::new(Jump { flow: flow }) as Box<dyn Statement>,
+    )(input)
+}
+
+fn parse_exit_statement(input: &str) -> IResult<&str, Box<dyn Statement>> {
+    map(
+        preceded(keyword("exit"), opt(preceded(blank, parse_expression))),
+        |status| Box::new(Exit { status: status }) as Box<dyn Statement>,
+    )(input)
+}
+
+fn parse_return_statement(input: &str) -> IResult<&str, Box<dyn Statement>> {
+    map(
+        preceded(keyword("return"), opt(preceded(blank, parse_expression))),
+        |value| Box::new(Return { value: value }) as Box<dyn Statement>,
+    )(input)
+}
+
+pub(super) fn parse_control_statement(input: &str) -> IResult<&str, Box<dyn Statement>> {
+    alt((
+        parse_jump_statement,
+        parse_exit_statement,
+        parse_return_statement,
+    ))(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utilities::empty_functions_and_variables, value::Value};
+
+    #[test]
+    fn test_parse_control_statement() {
+        let (functions, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+
+        let cases = vec![
+            ("break", ControlFlow::Break),
+            ("continue", ControlFlow::Continue),
+            ("next", ControlFlow::Next),
+            ("nextfile", ControlFlow::NextFile),
+            ("exit", ControlFlow::Exit(None)),
+            ("exit 1 + 2", ControlFlow::Exit(Some(3))),
+            ("return", ControlFlow::Return(Value::Uninitialized)),
+            (
+                r#"return "value""#,
+                ControlFlow::Return(Value::String("value".to_string())),
+            ),
+        ];
+        for (program, flow) in cases {
+            let result = parse_control_statement(program);
+            assert!(result.is_ok());
+            let (remaining, statement) = result.unwrap();
+            assert_eq!(remaining, "");
+            assert_eq!(statement.evaluate(&functions, &mut context), flow);
+        }
+
+        assert!(parse_control_statement("breakfast").is_err());
+    }
+}
@@ -0,0 +1,75 @@
+use nom::{
+    combinator::opt,
+    sequence::{pair, preceded},
+    IResult,
+};
+
+use super::Statement;
+use crate::{
+    context::{MutableContext, VariableStore},
+    control_flow::ControlFlow,
+    expression::{evaluate_subscripts, parse_subscripts, parse_variable_name, Expression},
+    function::Functions,
+    parse_utilities::{blank, keyword},
+};
+
+struct Delete {
+    array_name: String,
+    // Deleting without subscripts clears the entire array
+    subscripts: Option<Vec<Box<dyn Expression>>>,
+}
+
+impl Statement for Delete {
+    fn evaluate(&self, functions: &Functions, context: &mut MutableContext) -> ControlFlow {
+        match &self.subscripts {
+            Some(subscripts) => {
+                let key = evaluate_subscripts(subscripts, functions, context);
+                if !context.is_interrupted() {
+                    context.delete_array_element(&self.array_name, &key);
+                }
+            }
+            None => context.delete_array(&self.array_name),
+        }
+        ControlFlow::Normal
+    }
+}
+
+pub(super) fn parse_delete_statement(input: &str) -> IResult<&str, Box<dyn Statement>> {
+    let (i, (name, subscripts)) = preceded(
+        pair(keyword("delete"), blank),
+        pair(parse_variable_name, opt(parse_subscripts)),
+    )(input)?;
+
+    Result::Ok((
+        i,
+        Box::new(Delete {
+            array_name: name.to_string(),
+            subscripts: subscripts,
+        }),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utilities::empty_functions_and_variables, value::Value};
+
+    #[test]
+    fn delete_removes_elements() {
+        let (functions, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+        context.assign_array_element("arr", "a", Value::String("1".to_string()));
+        context.assign_array_element("arr", "b", Value::String("2".to_string()));
+
+        let result = parse_delete_statement(r#"delete arr["a"]"#);
+        assert!(result.is_ok());
+        result.unwrap().1.evaluate(&functions, &mut context);
+        assert!(!context.array_contains("arr", "a"));
+        assert!(context.array_contains("arr", "b"));
+
+        let result = parse_delete_statement(r#"delete arr"#);
+        assert!(result.is_ok());
+        result.unwrap().1.evaluate(&functions, &mut context);
+        assert!(!context.array_contains("arr", "b"));
+    }
+}
@@ -1,17 +1,12 @@
-use nom::{
-    bytes::complete::tag,
-    character::complete::{multispace0, one_of},
-    sequence::tuple,
-    IResult,
-};
+use nom::{character::complete::one_of, sequence::tuple, IResult};
 
-use super::{parse_action, Action, Statement};
+use super::{parse_body, Action, JumpScope, Statement};
 use crate::{
     context::MutableContext,
+    control_flow::ControlFlow,
     expression::{parse_expression, Expression},
     function::Functions,
-    printable::Printable,
-    value::UNINITIALIZED_VALUE,
+    parse_utilities::{blank, keyword, newline_opt},
 };
 
 struct DoWhile {
@@ -20,30 +15,42 @@ struct DoWhile {
 }
 
 impl Statement for DoWhile {
-    fn evaluate(&self, functions: &Functions, context: &mut MutableContext) -> Printable<()> {
-        let mut result = Printable::wrap(UNINITIALIZED_VALUE.clone());
+    fn evaluate(&self, functions: &Functions, context: &mut MutableContext) -> ControlFlow {
         loop {
-            result = result
-                .and_then(|_| self.body.output_for_line(functions, context))
-                .and_then(|_| self.condition.evaluate(functions, context));
-            if !result.value.coercion_to_boolean() {
+            match self.body.output_for_line(functions, context) {
+                ControlFlow::Break => break,
+                ControlFlow::Normal | ControlFlow::Continue => {}
+                flow => return flow,
+            }
+
+            let condition = self.condition.evaluate(functions, context);
+            if !condition.coercion_to_boolean() || context.is_interrupted() {
                 break;
             }
         }
-        result.map(|_| ())
+        ControlFlow::Normal
+    }
+
+    fn check_jumps(&self, scope: JumpScope) -> Result<(), &'static str> {
+        self.body.check_jumps(JumpScope {
+            in_loop: true,
+            ..scope
+        })
     }
 }
 
 pub(super) fn parse_do_while_statement(input: &str) -> IResult<&str, Box<dyn Statement>> {
-    let (i, (_, _, body, _, _, _, _, condition, _)) = tuple((
-        tag("do"),
-        multispace0,
-        parse_action,
-        multispace0,
-        tag("while"),
-        multispace0,
+    let (i, (_, _, body, _, _, _, _, _, condition, _, _)) = tuple((
+        keyword("do"),
+        newline_opt,
+        parse_body,
+        newline_opt,
+        keyword("while"),
+        blank,
         one_of("("),
+        blank,
         parse_expression,
+        blank,
         one_of(")"),
     ))(input)?;
     Result::Ok((
@@ -71,9 +78,28 @@ mod tests {
             } while (0)"#,
         );
         assert!(result.is_ok());
+        result.unwrap().1.evaluate(&functions, &mut context);
+        assert_eq!(context.take_output(), vec!["hello\n"]);
+    }
+
+    #[test]
+    fn do_while_statement_handles_break_and_continue() {
+        let (functions, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+
+        let result = parse_do_while_statement(
+            r#"do {
+                i++;
+                if (i == 1) { continue; } else {};
+                if (i == 3) { break; } else {};
+                print(i);
+            } while (1)"#,
+        );
+        assert!(result.is_ok());
         assert_eq!(
-            result.unwrap().1.evaluate(&functions, &mut context).output,
-            vec!["hello"],
+            result.unwrap().1.evaluate(&functions, &mut context),
+            ControlFlow::Normal
         );
+        assert_eq!(context.take_output(), vec!["2\n"]);
     }
 }
@@ -0,0 +1,58 @@
+use nom::IResult;
+
+use super::Statement;
+use crate::{
+    context::MutableContext,
+    control_flow::ControlFlow,
+    expression::{parse_expression, Expression},
+    function::Functions,
+};
+
+/// An expression evaluated only for its side effects, e.g. `count[$1]++` or `store($0)`
+struct ExpressionStatement {
+    expression: Box<dyn Expression>,
+}
+
+impl Statement for ExpressionStatement {
+    fn evaluate(&self, functions: &Functions, context: &mut MutableContext) -> ControlFlow {
+        self.expression.evaluate(functions, context);
+        ControlFlow::Normal
+    }
+}
+
+pub(super) fn parse_expression_statement(input: &str) -> IResult<&str, Box<dyn Statement>> {
+    let (i, expression) = parse_expression(input)?;
+
+    Result::Ok((
+        i,
+        Box::new(ExpressionStatement {
+            expression: expression,
+        }),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        context::VariableStore,
+        test_utilities::empty_functions_and_variables,
+        value::{NumericValue, Value},
+    };
+
+    #[test]
+    fn expression_statement_applies_side_effects() {
+        let (functions, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+
+        let result = parse_expression_statement(r#"count["a"]++"#);
+        assert!(result.is_ok());
+        let statement = result.unwrap().1;
+        statement.evaluate(&functions, &mut context);
+        statement.evaluate(&functions, &mut context);
+        assert_eq!(
+            context.fetch_array_element("count", "a"),
+            Value::Numeric(NumericValue::Integer(2)),
+        );
+    }
+}
@@ -0,0 +1,120 @@
+use nom::{character::complete::one_of, sequence::tuple, IResult};
+
+use super::{parse_body, Action, JumpScope, Statement};
+use crate::{
+    context::{MutableContext, VariableStore},
+    control_flow::ControlFlow,
+    expression::{parse_assignable, parse_variable_name, AssignableExpression},
+    function::Functions,
+    parse_utilities::{blank, keyword, newline_opt},
+    value::Value,
+};
+
+struct ForIn {
+    variable: Box<dyn AssignableExpression>,
+    array_name: String,
+    body: Action,
+}
+
+impl Statement for ForIn {
+    fn evaluate(&self, functions: &Functions, context: &mut MutableContext) -> ControlFlow {
+        // Iterate over a snapshot so the body can safely add or delete elements
+        let keys = context.array_keys(&self.array_name);
+
+        for key in keys {
+            self.variable.assign(functions, context, Value::String(key));
+            if context.is_interrupted() {
+                return ControlFlow::Normal;
+            }
+            match self.body.output_for_line(functions, context) {
+                ControlFlow::Break => return ControlFlow::Normal,
+                ControlFlow::Normal | ControlFlow::Continue => {}
+                flow => return flow,
+            }
+        }
+        ControlFlow::Normal
+    }
+
+    fn check_jumps(&self, scope: JumpScope) -> Result<(), &'static str> {
+        self.body.check_jumps(JumpScope {
+            in_loop: true,
+            ..scope
+        })
+    }
+}
+
+pub(super) fn parse_for_in_statement(input: &str) -> IResult<&str, Box<dyn Statement>> {
+    let (i, (_, _, _, _, variable, _, _, _, array_name, _, _, _, body)) = tuple((
+        keyword("for"),
+        blank,
+        one_of("("),
+        blank,
+        parse_assignable,
+        blank,
+        keyword("in"),
+        blank,
+        parse_variable_name,
+        blank,
+        one_of(")"),
+        newline_opt,
+        parse_body,
+    ))(input)?;
+
+    Result::Ok((
+        i,
+        Box::new(ForIn {
+            variable: variable,
+            array_name: array_name.to_string(),
+            body: body,
+        }),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utilities::empty_functions_and_variables, value::NumericValue};
+
+    #[test]
+    fn test_parse_for_in_statement() {
+        let (functions, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+        context.assign_array_element("arr", "only", Value::Numeric(NumericValue::Integer(1)));
+
+        let result = parse_for_in_statement(
+            r#"for (key in arr) {
+                print(key, arr[key]);
+            }"#,
+        );
+        assert!(result.is_ok());
+        result.unwrap().1.evaluate(&functions, &mut context);
+        assert_eq!(context.take_output(), vec!["only 1\n"]);
+        assert_eq!(
+            context.fetch_variable("key"),
+            Value::String("only".to_string())
+        );
+    }
+
+    #[test]
+    fn deleting_during_iteration_is_safe() {
+        let (functions, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+        for key in vec!["a", "b", "c"] {
+            context.assign_array_element("arr", key, Value::Uninitialized);
+        }
+
+        let result = parse_for_in_statement(
+            r#"for (key in arr) {
+                delete arr;
+                count++;
+            }"#,
+        );
+        assert!(result.is_ok());
+        result.unwrap().1.evaluate(&functions, &mut context);
+        assert_eq!(
+            context.fetch_variable("count"),
+            Value::Numeric(NumericValue::Integer(3))
+        );
+        assert_eq!(context.array_keys("arr").len(), 0);
+    }
+}
@@ -0,0 +1,173 @@
+use nom::{character::complete::one_of, combinator::opt, sequence::tuple, IResult};
+
+use super::{
+    expression_statement::parse_expression_statement, parse_body, Action, JumpScope, Statement,
+};
+use crate::{
+    context::MutableContext,
+    control_flow::ControlFlow,
+    expression::{parse_expression, Expression},
+    function::Functions,
+    parse_utilities::{blank, keyword, newline_opt},
+};
+
+struct For {
+    init: Option<Box<dyn Statement>>,
+    condition: Option<Box<dyn Expression>>,
+    step: Option<Box<dyn Statement>>,
+    body: Action,
+}
+
+impl For {
+    fn check_condition(&self, functions: &Functions, context: &mut MutableContext) -> bool {
+        match &self.condition {
+            Some(condition) => condition.evaluate(functions, context).coercion_to_boolean(),
+            // An empty condition loops forever
+            None => true,
+        }
+    }
+
+    fn run_step(&self, functions: &Functions, context: &mut MutableContext) -> ControlFlow {
+        match &self.step {
+            Some(step) => step.evaluate(functions, context),
+            None => ControlFlow::Normal,
+        }
+    }
+}
+
+impl Statement for For {
+    fn evaluate(&self, functions: &Functions, context: &mut MutableContext) -> ControlFlow {
+        if let Some(init) = &self.init {
+            let flow = init.evaluate(functions, context);
+            if flow != ControlFlow::Normal || context.is_interrupted() {
+                return flow;
+            }
+        }
+        while self.check_condition(functions, context) && !context.is_interrupted() {
+            match self.body.output_for_line(functions, context) {
+                ControlFlow::Break => break,
+                ControlFlow::Normal | ControlFlow::Continue => {}
+                flow => return flow,
+            }
+            let flow = self.run_step(functions, context);
+            if flow != ControlFlow::Normal || context.is_interrupted() {
+                return flow;
+            }
+        }
+        ControlFlow::Normal
+    }
+
+    fn check_jumps(&self, scope: JumpScope) -> Result<(), &'static str> {
+        self.body.check_jumps(JumpScope {
+            in_loop: true,
+            ..scope
+        })
+    }
+}
+
+fn parse_simple_statement_opt(input: &str) -> IResult<&str, Option<Box<dyn Statement>>> {
+    opt(parse_expression_statement)(input)
+}
+
+pub(super) fn parse_for_statement(input: &str) -> IResult<&str, Box<dyn Statement>> {
+    let (i, (_, _, _, _, init, _, _, _, condition, _, _, _, step, _, _, _, body)) = tuple((
+        keyword("for"),
+        blank,
+        one_of("("),
+        blank,
+        parse_simple_statement_opt,
+        blank,
+        one_of(";"),
+        newline_opt,
+        opt(parse_expression),
+        blank,
+        one_of(";"),
+        newline_opt,
+        parse_simple_statement_opt,
+        blank,
+        one_of(")"),
+        newline_opt,
+        parse_body,
+    ))(input)?;
+
+    Result::Ok((
+        i,
+        Box::new(For {
+            init: init,
+            condition: condition,
+            step: step,
+            body: body,
+        }),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        context::VariableStore,
+        test_utilities::empty_functions_and_variables,
+        value::{NumericValue, Value},
+    };
+
+    #[test]
+    fn test_parse_for_statement() {
+        let (functions, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+
+        let result = parse_for_statement(
+            r#"for (i = 0; i < 3; i++) {
+                print(i);
+            }"#,
+        );
+        assert!(result.is_ok());
+        result.unwrap().1.evaluate(&functions, &mut context);
+        assert_eq!(context.take_output(), vec!["0\n", "1\n", "2\n"]);
+        assert_eq!(
+            context.fetch_variable("i"),
+            Value::Numeric(NumericValue::Integer(3))
+        );
+    }
+
+    #[test]
+    fn test_for_statement_with_empty_clauses() {
+        let (functions, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+
+        let result = parse_for_statement(
+            r#"for (; n < 2;) {
+                n++;
+            }"#,
+        );
+        assert!(result.is_ok());
+        result.unwrap().1.evaluate(&functions, &mut context);
+        assert_eq!(
+            context.fetch_variable("n"),
+            Value::Numeric(NumericValue::Integer(2))
+        );
+
+        let result = parse_for_statement(r#"for(;;) {}"#);
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().0, "");
+    }
+
+    #[test]
+    fn continue_still_runs_the_step() {
+        let (functions, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+
+        let result = parse_for_statement(
+            r#"for (i = 0; ; i++) {
+                if (i == 1) { continue; } else {};
+                if (i == 3) { break; } else {};
+                print(i);
+            }"#,
+        );
+        assert!(result.is_ok());
+        assert_eq!(
+            result.unwrap().1.evaluate(&functions, &mut context),
+            ControlFlow::Normal
+        );
+        assert_eq!(context.take_output(), vec!["0\n", "2\n"]);
+    }
+}
@@ -1,61 +1,68 @@
 use nom::{
-    bytes::complete::tag,
-    character::complete::{multispace0, one_of},
-    combinator::map,
-    sequence::tuple,
+    character::complete::one_of,
+    combinator::{map, opt},
+    sequence::{pair, preceded, tuple},
     IResult,
 };
 
-use super::{parse_action, Action, Statement};
+use super::{parse_body, Action, JumpScope, Statement};
 use crate::{
     context::MutableContext,
+    control_flow::ControlFlow,
     expression::{parse_expression, Expression},
     function::Functions,
-    printable::Printable,
+    parse_utilities::{blank, keyword, newline_opt},
 };
 
 struct IfElse {
     condition: Box<dyn Expression>,
     if_branch: Action,
-    else_branch: Action,
+    else_branch: Option<Action>,
 }
 
 impl Statement for IfElse {
-    fn evaluate(&self, functions: &Functions, context: &mut MutableContext) -> Printable<()> {
-        self.condition
-            .evaluate(functions, context)
-            .and_then(|value| {
-                if value.coercion_to_boolean() {
-                    self.if_branch.output_for_line(functions, context)
-                } else {
-                    self.else_branch.output_for_line(functions, context)
-                }
-            })
+    fn evaluate(&self, functions: &Functions, context: &mut MutableContext) -> ControlFlow {
+        let value = self.condition.evaluate(functions, context);
+        if context.is_interrupted() {
+            ControlFlow::Normal
+        } else if value.coercion_to_boolean() {
+            self.if_branch.output_for_line(functions, context)
+        } else if let Some(else_branch) = &self.else_branch {
+            else_branch.output_for_line(functions, context)
+        } else {
+            ControlFlow::Normal
+        }
+    }
+
+    fn check_jumps(&self, scope: JumpScope) -> Result<(), &'static str> {
+        self.if_branch.check_jumps(scope)?;
+        match &self.else_branch {
+            Some(else_branch) => else_branch.check_jumps(scope),
+            None => Ok(()),
+        }
     }
 }
 
 pub(super) fn parse_if_else_statement(input: &str) -> IResult<&str, Box<dyn Statement>> {
     let parse_if = map(
         tuple((
-            tag("if"),
-            multispace0,
+            keyword("if"),
+            blank,
             one_of("("),
-            multispace0,
+            blank,
             parse_expression,
-            multispace0,
+            blank,
             one_of(")"),
         )),
         |(_, _, _, _, expression, _, _)| expression,
     );
 
-    let (i, (condition, _, if_branch, _, _, _, else_branch)) = tuple((
+    // An `else` always belongs to the nearest `if`, since the body is parsed before looking for it
+    let (i, (condition, _, if_branch, else_branch)) = tuple((
         parse_if,
-        multispace0,
-        parse_action,
-        multispace0,
-        tag("else"),
-        multispace0,
-        parse_action,
+        newline_opt,
+        parse_body,
+        opt(preceded(pair(keyword("else"), newline_opt), parse_body)),
     ))(input)?;
     Result::Ok((
         i,
@@ -86,10 +93,8 @@ mod tests {
         )
         .unwrap()
         .1;
-        assert_eq!(
-            if_conditional.evaluate(&functions, &mut context).output,
-            vec!["if-branch"],
-        );
+        if_conditional.evaluate(&functions, &mut context);
+        assert_eq!(context.take_output(), vec!["if-branch\n"]);
 
         let else_conditional = parse_if_else_statement(
             r#"if ("") {
@@ -100,10 +105,8 @@ mod tests {
         )
         .unwrap()
         .1;
-        assert_eq!(
-            else_conditional.evaluate(&functions, &mut context).output,
-            vec!["else"],
-        );
+        else_conditional.evaluate(&functions, &mut context);
+        assert_eq!(context.take_output(), vec!["else\n"]);
     }
 
     #[test]
@@ -117,13 +120,54 @@ mod tests {
         } else {}"#,
         );
         assert!(result.is_ok());
+        Action {
+            statements: vec![result.unwrap().1],
+        }
+        .output_for_line(&functions, &mut context);
+        assert_eq!(context.take_output(), vec!["hello\n"]);
+    }
+
+    #[test]
+    fn bodies_may_be_single_statements() {
+        let (functions, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+
+        for (program, expected) in vec![
+            ("if (1) print \"yes\"\n", vec!["yes\n"]),
+            ("if (0) print \"yes\"\n", vec![]),
+            ("if (0) print \"yes\"; else print \"no\"\n", vec!["no\n"]),
+            (
+                "if (0)\n  print \"yes\"\nelse\n  print \"no\"\n",
+                vec!["no\n"],
+            ),
+            // The else pairs with the inner if
+            ("if (0) if (1) print \"a\"; else print \"b\"\n", vec![]),
+            ("if (1) if (0) print \"a\"; else print \"b\"\n", vec!["b\n"]),
+        ] {
+            // Statements end with a newline, so each program includes one
+            let (remaining, statement) = parse_if_else_statement(program).unwrap();
+            assert_eq!(remaining, "");
+            statement.evaluate(&functions, &mut context);
+            assert_eq!(context.take_output(), expected);
+        }
+    }
+
+    #[test]
+    fn if_else_propagates_control_flow() {
+        let (functions, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+
+        let result = parse_if_else_statement(
+            r#"if (0) {
+                next;
+            } else {
+                exit 2;
+            }"#,
+        );
+        assert!(result.is_ok());
         assert_eq!(
-            Action {
-                statements: vec![result.unwrap().1]
-            }
-            .output_for_line(&functions, &mut context)
-            .output,
-            vec!["hello"],
+            result.unwrap().1.evaluate(&functions, &mut context),
+            ControlFlow::Exit(Some(2)),
         );
     }
 }
@@ -1,44 +1,80 @@
 use nom::{
     branch::alt,
-    character::complete::{multispace0, one_of},
+    character::complete::one_of,
     combinator::map,
     multi::many0,
-    sequence::{delimited, terminated, tuple},
+    sequence::{delimited, pair, terminated},
     IResult,
 };
 
-use crate::{context::MutableContext, function::Functions, printable::Printable};
+use crate::{
+    context::MutableContext,
+    control_flow::ControlFlow,
+    function::Functions,
+    parse_utilities::{newline_opt, statement_end},
+};
 
-mod assign;
+mod control;
+mod delete;
 mod do_while;
+mod expression_statement;
+mod for_in;
+mod for_statement;
 mod if_else;
 mod print;
+mod printf;
+mod redirection;
 mod while_statement;
 
 pub(crate) struct Action {
     statements: Vec<Box<dyn Statement>>,
 }
 
+/// Which of `break`, `continue`, and `return` the statements around a statement can handle
+#[derive(Clone, Copy)]
+pub(crate) struct JumpScope {
+    pub(crate) in_loop: bool,
+    pub(crate) in_function: bool,
+}
+
 impl Action {
+    /// The `{ print }` action used by items that only have a pattern
+    pub(crate) fn print_record() -> Action {
+        Action {
+            statements: vec![print::print_record()],
+        }
+    }
+
     pub(crate) fn output_for_line(
         &self,
         functions: &Functions,
         context: &mut MutableContext,
-    ) -> Printable<()> {
+    ) -> ControlFlow {
+        for statement in &self.statements {
+            let flow = statement.evaluate(functions, context);
+            // A function called by the statement may have ended the record early
+            let flow = context.take_interrupted_flow().unwrap_or(flow);
+            if flow != ControlFlow::Normal {
+                return flow;
+            }
+        }
+        ControlFlow::Normal
+    }
+
+    /// `break` and `continue` are only allowed within loops, and `return` within functions
+    pub(crate) fn check_jumps(&self, scope: JumpScope) -> Result<(), &'static str> {
         self.statements
             .iter()
-            .fold(Printable::wrap(()), |result, statement| {
-                result.and_then(|_| statement.evaluate(functions, context))
-            })
+            .try_for_each(|statement| statement.check_jumps(scope))
     }
 }
 
 pub(crate) fn parse_action(input: &str) -> IResult<&str, Action> {
     map(
         delimited(
-            tuple((one_of("{"), multispace0)),
+            pair(one_of("{"), newline_opt),
             parse_statements,
-            tuple((multispace0, one_of("}"))),
+            pair(newline_opt, one_of("}")),
         ),
         move |statements| Action {
             statements: statements,
@@ -47,24 +83,71 @@ pub(crate) fn parse_action(input: &str) -> IResult<&str, Action> {
 }
 
 trait Statement {
-    fn evaluate(&self, functions: &Functions, context: &mut MutableContext) -> Printable<()>;
+    fn evaluate(&self, functions: &Functions, context: &mut MutableContext) -> ControlFlow;
+
+    /// Statements which contain others check those too, once the whole program has been parsed
+    fn check_jumps(&self, _scope: JumpScope) -> Result<(), &'static str> {
+        Ok(())
+    }
+}
+
+/// A `{ ... }` block nested as a statement
+impl Statement for Action {
+    fn evaluate(&self, functions: &Functions, context: &mut MutableContext) -> ControlFlow {
+        self.output_for_line(functions, context)
+    }
+
+    fn check_jumps(&self, scope: JumpScope) -> Result<(), &'static str> {
+        Action::check_jumps(self, scope)
+    }
 }
 
+/// Statements ending in an action need no terminator, while all others end with a newline, a
+/// semicolon, or the closing brace of the enclosing action. Lone semicolons are empty statements.
 fn parse_statements(input: &str) -> IResult<&str, Vec<Box<dyn Statement>>> {
-    let parse_single_statement = terminated(
-        parse_simple_statement,
-        tuple((multispace0, one_of(";"), multispace0)),
-    );
-    many0(parse_single_statement)(input)
+    map(many0(parse_statement), |statements| {
+        statements.into_iter().flatten().collect()
+    })(input)
 }
 
-fn parse_simple_statement(input: &str) -> IResult<&str, Box<dyn Statement>> {
+fn parse_statement(input: &str) -> IResult<&str, Option<Box<dyn Statement>>> {
     alt((
-        print::parse_print_statement,
+        map(terminated(parse_compound_statement, newline_opt), Some),
+        map(terminated(parse_simple_statement, statement_end), Some),
+        map(pair(one_of(";"), newline_opt), |_| None),
+    ))(input)
+}
+
+/// The body of an `if` or a loop: either a braced action or a single statement
+fn parse_body(input: &str) -> IResult<&str, Action> {
+    alt((
+        terminated(parse_action, newline_opt),
+        map(parse_statement, |statement| Action {
+            statements: statement.into_iter().collect(),
+        }),
+    ))(input)
+}
+
+fn parse_compound_statement(input: &str) -> IResult<&str, Box<dyn Statement>> {
+    alt((
+        map(parse_action, |action| {
+            Box::new(action) as Box<dyn Statement>
+        }),
         if_else::parse_if_else_statement,
         while_statement::parse_while_statement,
+        for_in::parse_for_in_statement,
+        for_statement::parse_for_statement,
+    ))(input)
+}
+
+fn parse_simple_statement(input: &str) -> IResult<&str, Box<dyn Statement>> {
+    alt((
+        printf::parse_printf_statement,
+        print::parse_print_statement,
         do_while::parse_do_while_statement,
-        assign::parse_assign_statement,
+        delete::parse_delete_statement,
+        control::parse_control_statement,
+        expression_statement::parse_expression_statement,
     ))(input)
 }
 
@@ -80,14 +163,8 @@ mod tests {
 
         let result = parse_action(r#"{ print("hello"); }"#);
         assert!(result.is_ok());
-        assert_eq!(
-            result
-                .unwrap()
-                .1
-                .output_for_line(&functions, &mut context)
-                .output,
-            vec!["hello"],
-        );
+        result.unwrap().1.output_for_line(&functions, &mut context);
+        assert_eq!(context.take_output(), vec!["hello\n"]);
 
         let result = parse_action(
             r#"{
@@ -97,13 +174,60 @@ mod tests {
         }"#,
         );
         assert!(result.is_ok());
+        result.unwrap().1.output_for_line(&functions, &mut context);
+        assert_eq!(
+            context.take_output(),
+            vec!["1\n", "2 extra arg\n", "hello\n",]
+        );
+    }
+
+    #[test]
+    fn control_flow_stops_remaining_statements() {
+        let (functions, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+
+        let result = parse_action(r#"{ print("before"); next; print("after"); }"#);
+        assert!(result.is_ok());
         assert_eq!(
-            result
-                .unwrap()
-                .1
-                .output_for_line(&functions, &mut context)
-                .output,
-            vec!["1", "2 extra arg", "hello",],
+            result.unwrap().1.output_for_line(&functions, &mut context),
+            ControlFlow::Next
         );
+        assert_eq!(context.take_output(), vec!["before\n"]);
+    }
+
+    #[test]
+    fn statements_end_with_newlines_or_semicolons() {
+        let (functions, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+        context.set_record_with_line("a b");
+
+        let result = parse_action(
+            r#"{ # leading comment
+            x = 1
+            ;;
+            while (x < 3) { print x; x = x + 1 }
+            print "done" # trailing comment
+
+            if (x == 3)
+            {
+                print $2
+            }
+            else { print $1 }; print
+        }"#,
+        );
+        assert!(result.is_ok());
+        let (remaining, action) = result.unwrap();
+        assert_eq!(remaining, "");
+        action.output_for_line(&functions, &mut context);
+        assert_eq!(
+            context.take_output(),
+            vec!["1\n", "2\n", "done\n", "b\n", "a b\n"]
+        );
+
+        // Newlines end statements, so they can't split an expression
+        let action = parse_action("{ x = 1\n(2) ; print x }").unwrap().1;
+        action.output_for_line(&functions, &mut context);
+        assert_eq!(context.take_output(), vec!["1\n"]);
+        assert!(parse_action("{ print 1 print 2 }").is_err());
     }
 }
@@ -1,54 +1,105 @@
 use nom::{
-    bytes::complete::tag,
-    character::complete::{multispace0, one_of},
-    multi::separated_list,
-    sequence::delimited,
+    branch::alt,
+    character::complete::one_of,
+    combinator::{map, not},
+    multi::{many1, separated_list},
+    sequence::{delimited, pair, preceded, terminated, tuple},
     IResult,
 };
 
-use super::Statement;
+use super::{
+    redirection::{parse_output_redirection, write_output, Redirection},
+    Statement,
+};
 use crate::{
     context::{MutableContext, VariableStore},
-    expression::{parse_expression, Expression},
+    control_flow::ControlFlow,
+    expression::{parse_expression, parse_print_expression, Expression},
     function::Functions,
-    printable::Printable,
+    parse_utilities::{blank, keyword, newline_opt},
 };
 
+/// Without any expressions, prints the whole record
 struct Print {
     expressions: Vec<Box<dyn Expression>>,
+    redirection: Option<Redirection>,
 }
 
 impl Statement for Print {
-    fn evaluate(&self, functions: &Functions, context: &mut MutableContext) -> Printable<()> {
-        self.expressions
-            .iter()
-            .fold(Printable::wrap(vec![]), |result, e| {
-                result.and_then(|mut vec| {
-                    e.evaluate(functions, context).map(|value| {
-                        vec.push(value.coerce_to_string());
-                        vec
-                    })
+    fn evaluate(&self, functions: &Functions, context: &mut MutableContext) -> ControlFlow {
+        let strings: Vec<String> = if self.expressions.is_empty() {
+            vec![context.fetch_field(0).coerce_to_string()]
+        } else {
+            self.expressions
+                .iter()
+                .map(|e| {
+                    let value = e.evaluate(functions, context);
+                    context.coerce_to_output_string(&value)
                 })
-            })
-            .and_then(|strings| Printable {
-                value: (),
-                output: vec![strings.join(&context.fetch_variable("OFS").coerce_to_string())],
-            })
+                .collect()
+        };
+        // Values are separated by OFS, and the whole line is terminated by ORS
+        let line = format!(
+            "{}{}",
+            strings.join(&context.fetch_variable("OFS").coerce_to_string()),
+            context.fetch_variable("ORS").coerce_to_string()
+        );
+        write_output(&self.redirection, line, functions, context)
     }
 }
 
+pub(super) fn print_record() -> Box<dyn Statement> {
+    Box::new(Print {
+        expressions: vec![],
+        redirection: None,
+    })
+}
+
 pub(super) fn parse_print_statement(input: &str) -> IResult<&str, Box<dyn Statement>> {
-    let parse_separator = delimited(multispace0, one_of(","), multispace0);
-    let parse_expression_list = separated_list(parse_separator, parse_expression);
+    let parse_separator = || tuple((blank, one_of(","), newline_opt));
+    // A single parenthesized expression is just a grouping, so the parenthesized form needs two,
+    // and a list followed by `in` is a subscript for membership, as in `print (i, j) in array`.
+    // Falling back to the plain list re-parses the statement once, but nested parentheses within
+    // it are only ever parsed once.
+    let parse_grouped_expression_list = terminated(
+        delimited(
+            pair(one_of("("), blank),
+            map(
+                pair(
+                    parse_expression,
+                    many1(preceded(parse_separator(), parse_expression)),
+                ),
+                |(first, mut rest)| {
+                    rest.insert(0, first);
+                    rest
+                },
+            ),
+            pair(blank, one_of(")")),
+        ),
+        not(pair(blank, keyword("in"))),
+    );
+    let parse_expression_list = separated_list(parse_separator(), parse_print_expression);
 
-    let (i, exprs) = delimited(tag("print("), parse_expression_list, one_of(")"))(input)?;
-    Result::Ok((i, Box::new(Print { expressions: exprs })))
+    let (i, (exprs, redirection)) = preceded(
+        pair(keyword("print"), blank),
+        pair(
+            alt((parse_grouped_expression_list, parse_expression_list)),
+            parse_output_redirection,
+        ),
+    )(input)?;
+    Result::Ok((
+        i,
+        Box::new(Print {
+            expressions: exprs,
+            redirection: redirection,
+        }),
+    ))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_utilities::empty_functions_and_variables;
+    use crate::{test_utilities::empty_functions_and_variables, value::Value};
 
     #[test]
     fn print_statement_produces_value() {
@@ -56,9 +107,68 @@ mod tests {
         let mut context = MutableContext::for_variables(&mut empty_variables);
 
         let print_statement = parse_print_statement(r#"print("hello")"#).unwrap().1;
+        print_statement.evaluate(&functions, &mut context);
+        assert_eq!(context.take_output(), vec!["hello\n"]);
+    }
+
+    #[test]
+    fn print_statements_without_parentheses() {
+        let (functions, mut empty_variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut empty_variables);
+        context.set_record_with_line("a b c");
+
+        for (program, remaining, expected) in vec![
+            ("print", "", "a b c\n"),
+            ("print }", "}", "a b c\n"),
+            ("print $3, $1\nprint", "\nprint", "c a\n"),
+            ("print(1, 2); x", "; x", "1 2\n"),
+            ("print (1)", "", "1\n"),
+            ("print $1,\n  $2", "", "a b\n"),
+            (r#"print $1 "-" $3, $2 $1"#, "", "a-c ba\n"),
+            ("print (1, 2) in a", "", "0\n"),
+        ] {
+            let (i, statement) = parse_print_statement(program).unwrap();
+            assert_eq!(i, remaining);
+            statement.evaluate(&functions, &mut context);
+            assert_eq!(context.take_output(), vec![expected]);
+        }
+        assert!(parse_print_statement("printed = 1").is_err());
+    }
+
+    #[test]
+    fn print_statements_end_with_the_output_record_separator() {
+        let (functions, mut empty_variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut empty_variables);
+        context.assign_variable("OFS", Value::String("-".to_string()));
+
+        for (separator, expected) in vec![("\r\n", "a-b\r\n"), ("", "a-b"), ("\0", "a-b\0")] {
+            context.assign_variable("ORS", Value::String(separator.to_string()));
+            let statement = parse_print_statement(r#"print "a", "b""#).unwrap().1;
+            statement.evaluate(&functions, &mut context);
+            assert_eq!(context.take_output(), vec![expected]);
+        }
+    }
+
+    #[test]
+    fn print_statements_can_redirect_to_files() {
+        let (functions, mut empty_variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut empty_variables);
+        let path = std::env::temp_dir().join("rust_awk_print_redirection_test.txt");
+        let empty_vec: Vec<&'static str> = vec![];
+
+        for program in vec![
+            format!(r#"print "a", (2 > 1) > "{}""#, path.display()),
+            format!(r#"print("b", 3) >> "{}""#, path.display()),
+        ] {
+            let (remaining, statement) = parse_print_statement(&program).unwrap();
+            assert_eq!(remaining, "");
+            statement.evaluate(&functions, &mut context);
+            assert_eq!(context.take_output(), empty_vec);
+        }
+        assert_eq!(context.close_stream(path.to_str().unwrap()), 0);
         assert_eq!(
-            print_statement.evaluate(&functions, &mut context).output,
-            vec!["hello"],
+            std::fs::read_to_string(&path).unwrap(),
+            "a 1\nb 3\n".to_string()
         );
     }
 }
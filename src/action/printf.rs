@@ -0,0 +1,89 @@
+use nom::{
+    branch::alt,
+    character::complete::one_of,
+    multi::separated_nonempty_list,
+    sequence::{delimited, pair, preceded, tuple},
+    IResult,
+};
+
+use super::{
+    redirection::{parse_output_redirection, write_output, Redirection},
+    Statement,
+};
+use crate::{
+    context::MutableContext,
+    control_flow::ControlFlow,
+    expression::{parse_expression, parse_print_expression, Expression},
+    function::Functions,
+    parse_utilities::{blank, keyword, newline_opt},
+    value::Value,
+};
+
+/// The first expression is the format, and the rest are the values it consumes
+struct Printf {
+    expressions: Vec<Box<dyn Expression>>,
+    redirection: Option<Redirection>,
+}
+
+impl Statement for Printf {
+    fn evaluate(&self, functions: &Functions, context: &mut MutableContext) -> ControlFlow {
+        let values: Vec<Value> = self
+            .expressions
+            .iter()
+            .map(|e| e.evaluate(functions, context))
+            .collect();
+        let format = context.coerce_to_string(&values[0]);
+        let text = context.sprintf(&format, &values[1..]);
+        write_output(&self.redirection, text, functions, context)
+    }
+}
+
+pub(super) fn parse_printf_statement(input: &str) -> IResult<&str, Box<dyn Statement>> {
+    let parse_separator = || tuple((blank, one_of(","), newline_opt));
+
+    let (i, (exprs, redirection)) = preceded(
+        pair(keyword("printf"), blank),
+        pair(
+            alt((
+                delimited(
+                    pair(one_of("("), blank),
+                    separated_nonempty_list(parse_separator(), parse_expression),
+                    pair(blank, one_of(")")),
+                ),
+                separated_nonempty_list(parse_separator(), parse_print_expression),
+            )),
+            parse_output_redirection,
+        ),
+    )(input)?;
+    Result::Ok((
+        i,
+        Box::new(Printf {
+            expressions: exprs,
+            redirection: redirection,
+        }),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utilities::empty_functions_and_variables;
+
+    #[test]
+    fn printf_statement_formats_values() {
+        let (functions, mut empty_variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut empty_variables);
+
+        let result = parse_printf_statement(r#"printf("%-5s|%3d\n", "ab", 7)"#);
+        assert!(result.is_ok());
+        result.unwrap().1.evaluate(&functions, &mut context);
+        assert_eq!(context.take_output(), vec!["ab   |  7\n"]);
+
+        let result = parse_printf_statement(r#"printf "%s=%.2f", "pi", 3.14159"#);
+        assert!(result.is_ok());
+        let (remaining, statement) = result.unwrap();
+        assert_eq!(remaining, "");
+        statement.evaluate(&functions, &mut context);
+        assert_eq!(context.take_output(), vec!["pi=3.14"]);
+    }
+}
@@ -0,0 +1,64 @@
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    combinator::{map, opt},
+    sequence::{preceded, separated_pair},
+    IResult,
+};
+
+use crate::{
+    context::{MutableContext, StreamKind},
+    control_flow::ControlFlow,
+    expression::{parse_print_expression, Expression},
+    function::Functions,
+    parse_utilities::blank,
+};
+
+/// Where `print` and `printf` send their output instead of standard output
+pub(super) struct Redirection {
+    kind: StreamKind,
+    target: Box<dyn Expression>,
+}
+
+/// Writes to standard output, or to the stream named by the redirection
+pub(super) fn write_output(
+    redirection: &Option<Redirection>,
+    text: String,
+    functions: &Functions,
+    context: &mut MutableContext,
+) -> ControlFlow {
+    // Nothing is written if evaluating the values called a function which ended the record
+    if context.is_interrupted() {
+        return ControlFlow::Normal;
+    }
+    match redirection {
+        None => context.write_output(&text),
+        Some(Redirection { kind, target }) => {
+            let value = target.evaluate(functions, context);
+            let name = context.coerce_to_string(&value);
+            if !context.is_interrupted() {
+                context.write_to_stream(*kind, &name, &text);
+            }
+        }
+    }
+    ControlFlow::Normal
+}
+
+pub(super) fn parse_output_redirection(input: &str) -> IResult<&str, Option<Redirection>> {
+    let parse_kind = alt((
+        map(tag(">>"), |_| StreamKind::AppendFile),
+        map(tag(">"), |_| StreamKind::File),
+        map(tag("|"), |_| StreamKind::Pipe),
+    ));
+
+    opt(map(
+        preceded(
+            blank,
+            separated_pair(parse_kind, blank, parse_print_expression),
+        ),
+        |(kind, target)| Redirection {
+            kind: kind,
+            target: target,
+        },
+    ))(input)
+}
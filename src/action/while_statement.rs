@@ -1,16 +1,12 @@
-use nom::{
-    bytes::complete::tag,
-    character::complete::{multispace0, one_of},
-    sequence::tuple,
-    IResult,
-};
+use nom::{character::complete::one_of, sequence::tuple, IResult};
 
-use super::{parse_action, Action, Statement};
+use super::{parse_body, Action, JumpScope, Statement};
 use crate::{
     context::MutableContext,
+    control_flow::ControlFlow,
     expression::{parse_expression, Expression},
     function::Functions,
-    printable::Printable,
+    parse_utilities::{blank, keyword, newline_opt},
 };
 
 struct While {
@@ -19,32 +15,41 @@ struct While {
 }
 
 impl Statement for While {
-    fn evaluate(&self, functions: &Functions, context: &mut MutableContext) -> Printable<()> {
-        let mut result = self.condition.evaluate(functions, context);
-        loop {
-            if result.value.coercion_to_boolean() {
-                result = result
-                    .and_then(|_| self.body.output_for_line(functions, context))
-                    .and_then(|_| self.condition.evaluate(functions, context));
-            } else {
-                break;
+    fn evaluate(&self, functions: &Functions, context: &mut MutableContext) -> ControlFlow {
+        while self
+            .condition
+            .evaluate(functions, context)
+            .coercion_to_boolean()
+            && !context.is_interrupted()
+        {
+            match self.body.output_for_line(functions, context) {
+                ControlFlow::Break => break,
+                ControlFlow::Normal | ControlFlow::Continue => {}
+                flow => return flow,
             }
         }
-        result.map(|_| ())
+        ControlFlow::Normal
+    }
+
+    fn check_jumps(&self, scope: JumpScope) -> Result<(), &'static str> {
+        self.body.check_jumps(JumpScope {
+            in_loop: true,
+            ..scope
+        })
     }
 }
 
 pub(super) fn parse_while_statement(input: &str) -> IResult<&str, Box<dyn Statement>> {
     let (i, (_, _, _, _, condition, _, _, _, body)) = tuple((
-        tag("while"),
-        multispace0,
+        keyword("while"),
+        blank,
         one_of("("),
-        multispace0,
+        blank,
         parse_expression,
-        multispace0,
+        blank,
         one_of(")"),
-        multispace0,
-        parse_action,
+        newline_opt,
+        parse_body,
     ))(input)?;
 
     Result::Ok((
@@ -73,9 +78,60 @@ mod tests {
         );
         let empty_vec: Vec<&'static str> = vec![];
         assert!(result.is_ok());
+        result.unwrap().1.evaluate(&functions, &mut context);
+        assert_eq!(context.take_output(), empty_vec);
+    }
+
+    #[test]
+    fn while_statement_handles_break_and_continue() {
+        let (functions, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+
+        let result = parse_while_statement(
+            r#"while (1) {
+                i++;
+                if (i == 2) { continue; } else {};
+                if (i > 3) { break; } else {};
+                print(i);
+            }"#,
+        );
+        assert!(result.is_ok());
+        assert_eq!(
+            result.unwrap().1.evaluate(&functions, &mut context),
+            ControlFlow::Normal
+        );
+        assert_eq!(context.take_output(), vec!["1\n", "3\n"]);
+    }
+
+    #[test]
+    fn while_statement_propagates_other_control_flow() {
+        let (functions, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+
+        let result = parse_while_statement(
+            r#"while (1) {
+                print("once");
+                next;
+            }"#,
+        );
+        assert!(result.is_ok());
         assert_eq!(
-            result.unwrap().1.evaluate(&functions, &mut context).output,
-            empty_vec,
+            result.unwrap().1.evaluate(&functions, &mut context),
+            ControlFlow::Next
         );
+        assert_eq!(context.take_output(), vec!["once\n"]);
+    }
+
+    #[test]
+    fn while_statement_with_single_statement_body() {
+        let (functions, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+
+        let result = parse_while_statement("while (++i < 3)\n    print(i)\n");
+        assert!(result.is_ok());
+        let (remaining, statement) = result.unwrap();
+        assert_eq!(remaining, "");
+        statement.evaluate(&functions, &mut context);
+        assert_eq!(context.take_output(), vec!["1\n", "2\n"]);
     }
 }
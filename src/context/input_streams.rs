@@ -0,0 +1,213 @@
+use std::collections::{HashMap, VecDeque};
+use std::fs::File;
+use std::io::{stdin, BufRead, BufReader, Read, Stdin};
+use std::process::{Child, Command, Stdio};
+
+use super::{VariableStore, Variables};
+use crate::value::{NumericValue, Value};
+
+type IOResult = std::io::Result<usize>;
+
+pub(crate) trait LineReadable {
+    fn trait_read_line(&mut self, buffer: &mut String) -> IOResult;
+}
+
+impl LineReadable for Stdin {
+    fn trait_read_line(&mut self, buffer: &mut String) -> IOResult {
+        self.read_line(buffer)
+    }
+}
+
+impl<T: Read> LineReadable for BufReader<T> {
+    fn trait_read_line(&mut self, buffer: &mut String) -> IOResult {
+        self.read_line(buffer)
+    }
+}
+
+/// Reads the next line without its trailing newline, or `None` at the end of the input
+pub(crate) fn read_record(reader: &mut dyn LineReadable) -> std::io::Result<Option<String>> {
+    let mut buffer = String::new();
+    if reader.trait_read_line(&mut buffer)? == 0 {
+        return Ok(None);
+    }
+    if buffer.ends_with('\n') {
+        buffer.pop();
+    }
+    Ok(Some(buffer))
+}
+
+/// The records read by the main loop and by plain `getline`: each file named on the command
+/// line in turn, or standard input without any. Files are only opened once they are needed.
+pub(crate) struct MainInput {
+    paths: VecDeque<String>,
+    current: Option<Box<dyn LineReadable>>,
+}
+
+impl MainInput {
+    pub(crate) fn for_paths(paths: Vec<String>) -> MainInput {
+        let current: Option<Box<dyn LineReadable>> = if paths.is_empty() {
+            Some(Box::new(stdin()))
+        } else {
+            None
+        };
+        MainInput {
+            paths: paths.into(),
+            current: current,
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn for_reader(reader: Box<dyn LineReadable>) -> MainInput {
+        MainInput {
+            paths: VecDeque::new(),
+            current: Some(reader),
+        }
+    }
+
+    /// Moves on to the next file once the current one runs out, starting FNR over. Files which
+    /// can't be opened are reported and skipped.
+    pub(crate) fn read_record(
+        &mut self,
+        variables: &mut Variables,
+    ) -> std::io::Result<Option<String>> {
+        loop {
+            if self.current.is_none() {
+                let path = match self.paths.pop_front() {
+                    Some(path) => path,
+                    None => return Ok(None),
+                };
+                match File::open(&path) {
+                    Ok(file) => self.current = Some(Box::new(BufReader::new(file))),
+                    Err(error) => {
+                        eprintln!("Error encountered: {}", error);
+                        continue;
+                    }
+                }
+                variables.assign_variable("FNR", Value::Numeric(NumericValue::Integer(0)));
+            }
+
+            match read_record(self.current.as_mut().unwrap().as_mut()) {
+                Ok(Some(line)) => return Ok(Some(line)),
+                Ok(None) => self.current = None,
+                Err(error) => {
+                    self.current = None;
+                    return Err(error);
+                }
+            }
+        }
+    }
+
+    /// The rest of the current file is skipped, as for `nextfile`
+    pub(crate) fn skip_file(&mut self) {
+        self.current = None;
+    }
+}
+
+struct InputStream {
+    reader: Box<dyn LineReadable>,
+    // Only set when reading from the output of a command
+    child: Option<Child>,
+}
+
+impl InputStream {
+    fn open_file(name: &str) -> std::io::Result<InputStream> {
+        Ok(InputStream {
+            reader: Box::new(BufReader::new(File::open(name)?)),
+            child: None,
+        })
+    }
+
+    fn open_command(command: &str) -> std::io::Result<InputStream> {
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .stdout(Stdio::piped())
+            .spawn()?;
+        Ok(InputStream {
+            reader: Box::new(BufReader::new(child.stdout.take().unwrap())),
+            child: Some(child),
+        })
+    }
+
+    /// Closing a command waits for it to finish and returns its exit status
+    fn close(self) -> i64 {
+        let InputStream { reader, child } = self;
+        drop(reader);
+        match child.map(|mut child| child.wait()) {
+            None => 0,
+            Some(Ok(status)) => status.code().unwrap_or(-1) as i64,
+            Some(Err(_)) => -1,
+        }
+    }
+}
+
+/// The files and commands read by `getline`, keyed by the name used to open them so that each
+/// `getline` continues where the last one on the same name stopped
+pub(crate) struct InputStreams {
+    streams: HashMap<String, InputStream>,
+}
+
+impl InputStreams {
+    pub(crate) fn new() -> InputStreams {
+        InputStreams {
+            streams: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn read_from_file(&mut self, name: &str) -> std::io::Result<Option<String>> {
+        self.read_from(name, InputStream::open_file)
+    }
+
+    pub(crate) fn read_from_command(&mut self, command: &str) -> std::io::Result<Option<String>> {
+        self.read_from(command, InputStream::open_command)
+    }
+
+    fn read_from<F>(&mut self, name: &str, open: F) -> std::io::Result<Option<String>>
+    where
+        F: FnOnce(&str) -> std::io::Result<InputStream>,
+    {
+        if !self.streams.contains_key(name) {
+            self.streams.insert(name.to_string(), open(name)?);
+        }
+        read_record(self.streams.get_mut(name).unwrap().reader.as_mut())
+    }
+
+    /// Returns -1 when nothing is open under `name`
+    pub(crate) fn close(&mut self, name: &str) -> i64 {
+        match self.streams.remove(name) {
+            Some(stream) => stream.close(),
+            None => -1,
+        }
+    }
+
+    pub(crate) fn close_all(&mut self) {
+        for (_, stream) in self.streams.drain() {
+            stream.close();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commands_are_read_a_line_at_a_time() {
+        let mut streams = InputStreams::new();
+        let command = "printf 'one\\ntwo'; exit 4";
+
+        assert_eq!(
+            streams.read_from_command(command).unwrap(),
+            Some("one".to_string())
+        );
+        assert_eq!(
+            streams.read_from_command(command).unwrap(),
+            Some("two".to_string())
+        );
+        assert_eq!(streams.read_from_command(command).unwrap(), None);
+        assert_eq!(streams.close(command), 4);
+        assert_eq!(streams.close(command), -1);
+
+        assert!(streams.read_from_file("/nonexistent/rust-awk").is_err());
+    }
+}
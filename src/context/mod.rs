@@ -1,65 +1,220 @@
-use crate::value::{NumericValue, Value};
+use std::io::Write;
 
+use crate::{
+    control_flow::ControlFlow,
+    format,
+    value::{NumericValue, Value},
+};
+
+mod input_streams;
+mod output_sink;
+mod output_streams;
+mod random;
+mod record;
 pub(crate) mod stack_frame;
 pub(crate) mod variables;
 
-use stack_frame::StackFrame;
-pub(crate) use variables::Variables;
-
-struct Record<'a> {
-    full_line: &'a str,
-    fields: Vec<&'a str>,
-}
+#[cfg(test)]
+pub(crate) use input_streams::LineReadable;
+pub(crate) use input_streams::MainInput;
+use output_sink::OutputSink;
+pub(crate) use output_streams::StreamKind;
+use stack_frame::{Array, StackFrame, Variable};
+pub(crate) use variables::{FieldSeparator, Variables};
 
 pub(crate) trait VariableStore {
     fn fetch_variable(&self, variable_name: &str) -> Value;
 
     fn assign_variable(&mut self, variable_name: &str, value: Value);
+
+    fn fetch_array_element(&mut self, array_name: &str, key: &str) -> Value;
+
+    fn assign_array_element(&mut self, array_name: &str, key: &str, value: Value);
+
+    fn array_contains(&self, array_name: &str, key: &str) -> bool;
+
+    fn delete_array_element(&mut self, array_name: &str, key: &str);
+
+    fn delete_array(&mut self, array_name: &str);
+
+    fn array_keys(&self, array_name: &str) -> Vec<String>;
 }
 
 pub(crate) struct MutableContext<'a> {
     variables: &'a mut Variables,
-    record: Record<'a>,
+    interrupted_flow: Option<ControlFlow>,
+    // The main input, which plain `getline` reads from
+    input: Option<&'a mut MainInput>,
+    output: OutputSink<'a>,
 }
 
 impl<'a> MutableContext<'a> {
     pub(crate) fn fetch_field(&self, index: i64) -> Value {
-        match index {
-            i if i < 0 => panic!("Field indexes cannot be negative: {}", index),
-            i if i == 0 => Value::String(self.record.full_line.to_string()),
-            i => self
-                .record
-                .fields
-                .get((i - 1) as usize)
-                .map(|s| Value::String(s.to_string()))
-                .unwrap_or(Value::Uninitialized),
+        if index < 0 {
+            panic!("Field indexes cannot be negative: {}", index);
+        }
+        self.variables.fetch_field(index as usize)
+    }
+
+    /// Assigning `$0` re-splits the record, while assigning any other field rebuilds `$0`
+    pub(crate) fn assign_field(&mut self, index: i64, value: &Value) {
+        if index < 0 {
+            panic!("Field indexes cannot be negative: {}", index);
         }
+        let value = self.coerce_to_string(value);
+        self.variables.assign_field(index as usize, value);
     }
 
     pub(crate) fn for_variables(variables: &mut Variables) -> MutableContext {
-        let empty_record = variables.record_for_line("");
         MutableContext {
             variables: variables,
-            record: empty_record,
+            interrupted_flow: None,
+            input: None,
+            output: OutputSink::Collected(vec![]),
         }
     }
 
-    pub(crate) fn set_record_with_line(&mut self, line: &'a str) {
-        self.record = self.variables.record_for_line(line);
-        self.variables.assign_variable(
-            "NF",
-            Value::Numeric(NumericValue::Integer(self.record.fields.len() as i64)),
-        );
+    /// Without a writer, output is collected and can be retrieved with `take_output`
+    pub(crate) fn writing_to(self, output: &'a mut dyn Write) -> MutableContext<'a> {
+        MutableContext {
+            output: OutputSink::Writer(output),
+            ..self
+        }
+    }
+
+    pub(crate) fn reading_from(self, input: &'a mut MainInput) -> MutableContext<'a> {
+        MutableContext {
+            input: Some(input),
+            ..self
+        }
+    }
+
+    pub(crate) fn split_with_field_separator<'b>(&self, text: &'b str) -> Vec<&'b str> {
+        self.variables.split_with_field_separator(text)
+    }
+
+    /// The next number in the sequence for the current `srand` seed
+    pub(crate) fn random_number(&mut self) -> f64 {
+        self.variables.random_numbers.next()
+    }
+
+    pub(crate) fn seed_random_numbers(&mut self, seed: NumericValue) -> NumericValue {
+        self.variables.random_numbers.reseed(seed)
+    }
+
+    /// Writes `text` to standard output
+    pub(crate) fn write_output(&mut self, text: &str) {
+        self.output.write(text);
+    }
+
+    pub(crate) fn flush_output(&mut self) {
+        self.output.flush();
+    }
+
+    #[cfg(test)]
+    pub(crate) fn take_output(&mut self) -> Vec<String> {
+        self.output.take()
+    }
+
+    /// Writes `text` to the file or command named `name`, opening it first if needed
+    pub(crate) fn write_to_stream(&mut self, kind: StreamKind, name: &str, text: &str) {
+        self.variables.output_streams.write(kind, name, text);
+    }
+
+    pub(crate) fn flush_stream(&mut self, name: &str) -> i64 {
+        self.variables.output_streams.flush(name)
+    }
+
+    pub(crate) fn flush_all_streams(&mut self) -> i64 {
+        self.variables.output_streams.flush_all()
+    }
+
+    /// Closes whichever output or input stream is open under `name`
+    pub(crate) fn close_stream(&mut self, name: &str) -> i64 {
+        match self.variables.output_streams.close(name) {
+            -1 => self.variables.input_streams.close(name),
+            result => result,
+        }
+    }
+
+    /// The next record of the main input, or `None` once it is exhausted or if there is none
+    pub(crate) fn read_from_input(&mut self) -> std::io::Result<Option<String>> {
+        match &mut self.input {
+            Some(input) => input.read_record(self.variables),
+            None => Ok(None),
+        }
+    }
+
+    pub(crate) fn read_from_file(&mut self, name: &str) -> std::io::Result<Option<String>> {
+        self.variables.input_streams.read_from_file(name)
+    }
+
+    pub(crate) fn read_from_command(&mut self, command: &str) -> std::io::Result<Option<String>> {
+        self.variables.input_streams.read_from_command(command)
+    }
+
+    pub(crate) fn increment_variable(&mut self, variable_name: &str) {
+        self.variables.increment_variable(variable_name);
+    }
+
+    /// Numbers used as strings are converted according to CONVFMT
+    pub(crate) fn coerce_to_string(&self, value: &Value) -> String {
+        value.coerce_to_string_with_format(&self.fetch_variable("CONVFMT").coerce_to_string())
+    }
+
+    /// Numbers being printed are converted according to OFMT instead
+    pub(crate) fn coerce_to_output_string(&self, value: &Value) -> String {
+        value.coerce_to_string_with_format(&self.fetch_variable("OFMT").coerce_to_string())
+    }
+
+    /// `printf` and `sprintf` convert numbers given to `%s` according to CONVFMT
+    pub(crate) fn sprintf(&self, format: &str, arguments: &[Value]) -> String {
+        format::sprintf_with_conversion_format(
+            format,
+            arguments,
+            &self.fetch_variable("CONVFMT").coerce_to_string(),
+        )
     }
 
-    pub(crate) fn with_stack_frame<T, F>(&mut self, frame: StackFrame, f: F) -> T
+    pub(crate) fn set_record_with_line(&mut self, line: &str) {
+        self.variables.set_record(line);
+    }
+
+    /// Runs `f` with `frame` as the innermost scope, handing the frame back afterwards so the
+    /// caller can inspect what became of its variables
+    pub(crate) fn with_stack_frame<T, F>(&mut self, frame: StackFrame, f: F) -> (T, StackFrame)
     where
         F: FnOnce(&mut Self) -> T,
     {
         self.variables.function_variables.push(frame);
         let output = f(self);
-        self.variables.function_variables.pop();
-        output
+        let frame = self.variables.function_variables.pop().unwrap();
+        (output, frame)
+    }
+
+    pub(crate) fn fetch_binding(&self, variable_name: &str) -> Option<Variable> {
+        self.variables.fetch_binding(variable_name)
+    }
+
+    pub(crate) fn bind_array(&mut self, variable_name: &str, array: Array) {
+        self.variables.bind_array(variable_name, array);
+    }
+
+    /// Function calls are evaluated as expressions, which can't carry control flow. Statements
+    /// like `exit` and `next` inside a function body are parked here until the enclosing action
+    /// can act on them.
+    pub(crate) fn interrupt_with(&mut self, flow: ControlFlow) {
+        self.interrupted_flow = Some(flow);
+    }
+
+    /// Whether a function has parked control flow, in which case statements stop short and
+    /// leave it for the enclosing action to pick up
+    pub(crate) fn is_interrupted(&self) -> bool {
+        self.interrupted_flow.is_some()
+    }
+
+    pub(crate) fn take_interrupted_flow(&mut self) -> Option<ControlFlow> {
+        self.interrupted_flow.take()
     }
 }
 
@@ -71,6 +226,30 @@ impl VariableStore for MutableContext<'_> {
     fn assign_variable(&mut self, variable_name: &str, value: Value) {
         self.variables.assign_variable(variable_name, value);
     }
+
+    fn fetch_array_element(&mut self, array_name: &str, key: &str) -> Value {
+        self.variables.fetch_array_element(array_name, key)
+    }
+
+    fn assign_array_element(&mut self, array_name: &str, key: &str, value: Value) {
+        self.variables.assign_array_element(array_name, key, value);
+    }
+
+    fn array_contains(&self, array_name: &str, key: &str) -> bool {
+        self.variables.array_contains(array_name, key)
+    }
+
+    fn delete_array_element(&mut self, array_name: &str, key: &str) {
+        self.variables.delete_array_element(array_name, key);
+    }
+
+    fn delete_array(&mut self, array_name: &str) {
+        self.variables.delete_array(array_name);
+    }
+
+    fn array_keys(&self, array_name: &str) -> Vec<String> {
+        self.variables.array_keys(array_name)
+    }
 }
 
 #[cfg(test)]
@@ -95,6 +274,21 @@ mod tests {
         assert_eq!(Value::Uninitialized.coerce_to_string(), "");
     }
 
+    #[test]
+    fn conversions_follow_convfmt_and_ofmt() {
+        let mut variables = Variables::empty();
+        variables.assign_variable("CONVFMT", Value::String("%.2f".to_string()));
+        variables.assign_variable("OFMT", Value::String("%.3e".to_string()));
+        let context = MutableContext::for_variables(&mut variables);
+
+        let third = Value::Numeric(NumericValue::Float(1.0 / 3.0));
+        assert_eq!(context.coerce_to_string(&third), "0.33");
+        assert_eq!(context.coerce_to_output_string(&third), "3.333e-01");
+        let whole = Value::Numeric(NumericValue::Float(7.0));
+        assert_eq!(context.coerce_to_string(&whole), "7");
+        assert_eq!(context.coerce_to_output_string(&whole), "7");
+    }
+
     #[test]
     fn all_values_coerce_to_numerics() {
         // assert_eq!(
@@ -208,4 +402,26 @@ mod tests {
             Some(Value::String("new value".to_string())),
         );
     }
+
+    #[test]
+    fn unset_function_variable_becomes_local_array() {
+        let mut variables = Variables::empty();
+        let mut frame = StackFrame::empty();
+        frame.assign_variable("arr", Value::Uninitialized);
+
+        variables.function_variables = vec![frame];
+        variables.assign_array_element("arr", "key", Value::String("value".to_string()));
+
+        assert!(variables.array_contains("arr", "key"));
+        variables.function_variables.pop();
+        assert!(!variables.array_contains("arr", "key"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn scalars_cannot_be_subscripted() {
+        let mut variables = Variables::empty();
+        variables.assign_variable("foo", Value::String("value".to_string()));
+        variables.fetch_array_element("foo", "key");
+    }
 }
@@ -0,0 +1,74 @@
+use std::io::{Error, ErrorKind, Write};
+
+use crate::runtime_error::runtime_error;
+
+/// Where standard output goes: straight to a writer during a run, or collected in memory so
+/// that tests can inspect it
+pub(crate) enum OutputSink<'a> {
+    Collected(Vec<String>),
+    Writer(&'a mut dyn Write),
+}
+
+impl OutputSink<'_> {
+    pub(crate) fn write(&mut self, text: &str) {
+        match self {
+            OutputSink::Collected(output) => output.push(text.to_string()),
+            OutputSink::Writer(writer) => {
+                if let Err(e) = writer.write_all(text.as_bytes()) {
+                    output_failed(e);
+                }
+            }
+        }
+    }
+
+    pub(crate) fn flush(&mut self) {
+        if let OutputSink::Writer(writer) = self {
+            if let Err(e) = writer.flush() {
+                output_failed(e);
+            }
+        }
+    }
+
+    /// Hands back everything collected so far
+    #[cfg(test)]
+    pub(crate) fn take(&mut self) -> Vec<String> {
+        match self {
+            OutputSink::Collected(output) => std::mem::take(output),
+            OutputSink::Writer(_) => vec![],
+        }
+    }
+}
+
+/// Nobody reading the output any more, e.g. once `head` has seen enough, quietly ends the run as
+/// it would for any other awk. Other errors are reported.
+fn output_failed(error: Error) -> ! {
+    if error.kind() == ErrorKind::BrokenPipe {
+        // Rust ignores SIGPIPE, so this is the status a shell would give for being killed by it
+        std::process::exit(128 + 13);
+    }
+    runtime_error(&format!("could not write output: {}", error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FullDevice;
+
+    impl Write for FullDevice {
+        fn write(&mut self, _buffer: &[u8]) -> std::io::Result<usize> {
+            Err(Error::new(ErrorKind::Other, "no space left"))
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "could not write output: no space left")]
+    fn write_errors_are_reported() {
+        let mut device = FullDevice;
+        OutputSink::Writer(&mut device).write("text");
+    }
+}
@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::process::{Child, Command, Stdio};
+
+/// How a `print` or `printf` statement redirects its output
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum StreamKind {
+    /// `> file` truncates the file when it is first opened
+    File,
+    /// `>> file` appends to the file
+    AppendFile,
+    /// `| command` writes to the standard input of a shell command
+    Pipe,
+}
+
+enum OutputStream {
+    File(BufWriter<File>),
+    Pipe(Child),
+}
+
+impl OutputStream {
+    fn open(kind: StreamKind, name: &str) -> OutputStream {
+        match kind {
+            StreamKind::File | StreamKind::AppendFile => {
+                let file = OpenOptions::new()
+                    .write(true)
+                    .create(true)
+                    .append(kind == StreamKind::AppendFile)
+                    .truncate(kind == StreamKind::File)
+                    .open(name)
+                    .unwrap_or_else(|e| panic!("Could not open {} for output: {}", name, e));
+                OutputStream::File(BufWriter::new(file))
+            }
+            StreamKind::Pipe => {
+                let child = Command::new("sh")
+                    .arg("-c")
+                    .arg(name)
+                    .stdin(Stdio::piped())
+                    .spawn()
+                    .unwrap_or_else(|e| panic!("Could not run command {}: {}", name, e));
+                OutputStream::Pipe(child)
+            }
+        }
+    }
+
+    fn writer(&mut self) -> &mut dyn Write {
+        match self {
+            OutputStream::File(writer) => writer,
+            OutputStream::Pipe(child) => child.stdin.as_mut().unwrap(),
+        }
+    }
+
+    /// Closing a pipe waits for its command to finish and returns the command's exit status
+    fn close(mut self) -> i64 {
+        let flushed = self.writer().flush().is_ok();
+        match self {
+            OutputStream::File(_) if flushed => 0,
+            OutputStream::File(_) => -1,
+            OutputStream::Pipe(mut child) => {
+                // Dropping standard input sends the command its end of file
+                child.stdin.take();
+                match child.wait() {
+                    Ok(status) => status.code().unwrap_or(-1) as i64,
+                    Err(_) => -1,
+                }
+            }
+        }
+    }
+}
+
+/// The files and commands opened by output redirection, keyed by the name used to open them,
+/// so that every redirection to the same name shares one stream until it is closed
+pub(crate) struct OutputStreams {
+    streams: HashMap<String, OutputStream>,
+}
+
+impl OutputStreams {
+    pub(crate) fn new() -> OutputStreams {
+        OutputStreams {
+            streams: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn write(&mut self, kind: StreamKind, name: &str, text: &str) {
+        let stream = self
+            .streams
+            .entry(name.to_string())
+            .or_insert_with(|| OutputStream::open(kind, name));
+        if let Err(e) = stream.writer().write_all(text.as_bytes()) {
+            panic!("Could not write to {}: {}", name, e);
+        }
+    }
+
+    /// Returns -1 when nothing is open under `name`
+    pub(crate) fn flush(&mut self, name: &str) -> i64 {
+        match self
+            .streams
+            .get_mut(name)
+            .map(|stream| stream.writer().flush())
+        {
+            Some(Ok(_)) => 0,
+            _ => -1,
+        }
+    }
+
+    pub(crate) fn flush_all(&mut self) -> i64 {
+        self.streams
+            .values_mut()
+            .fold(0, |result, stream| match stream.writer().flush() {
+                Ok(_) => result,
+                Err(_) => -1,
+            })
+    }
+
+    /// Returns -1 when nothing is open under `name`
+    pub(crate) fn close(&mut self, name: &str) -> i64 {
+        match self.streams.remove(name) {
+            Some(stream) => stream.close(),
+            None => -1,
+        }
+    }
+
+    pub(crate) fn close_all(&mut self) {
+        for (_, stream) in self.streams.drain() {
+            stream.close();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::read_to_string;
+
+    #[test]
+    fn writes_to_the_same_name_share_a_stream() {
+        let path = std::env::temp_dir().join("rust_awk_output_streams_test.txt");
+        let name = path.to_str().unwrap();
+        let mut streams = OutputStreams::new();
+
+        streams.write(StreamKind::File, name, "first\n");
+        streams.write(StreamKind::File, name, "second\n");
+        assert_eq!(streams.flush(name), 0);
+        assert_eq!(read_to_string(&path).unwrap(), "first\nsecond\n");
+        assert_eq!(streams.close(name), 0);
+        assert_eq!(streams.close(name), -1);
+
+        // Reopening with `>>` keeps what is already there
+        streams.write(StreamKind::AppendFile, name, "third\n");
+        streams.close_all();
+        assert_eq!(read_to_string(&path).unwrap(), "first\nsecond\nthird\n");
+
+        streams.write(StreamKind::File, name, "fourth\n");
+        streams.close_all();
+        assert_eq!(read_to_string(&path).unwrap(), "fourth\n");
+    }
+
+    #[test]
+    fn closing_a_pipe_returns_its_exit_status() {
+        let mut streams = OutputStreams::new();
+
+        streams.write(StreamKind::Pipe, "cat > /dev/null; exit 3", "ignored\n");
+        assert_eq!(streams.close("cat > /dev/null; exit 3"), 3);
+        assert_eq!(streams.flush("cat > /dev/null; exit 3"), -1);
+    }
+}
@@ -0,0 +1,98 @@
+use crate::value::NumericValue;
+
+/// A small, deterministic generator (SplitMix64) so that a given seed always produces the same
+/// sequence from `rand()`
+pub(crate) struct RandomNumbers {
+    seed: NumericValue,
+    state: u64,
+}
+
+impl RandomNumbers {
+    pub(crate) fn new() -> RandomNumbers {
+        RandomNumbers::with_seed(NumericValue::Integer(0))
+    }
+
+    /// Whole numbers seed the same sequence however they are held, since `1` and `1.0` are the
+    /// same awk number
+    fn with_seed(seed: NumericValue) -> RandomNumbers {
+        let seed = match seed {
+            NumericValue::Float(f) if f.fract() == 0.0 && f.abs() < i64::MAX as f64 => {
+                NumericValue::Integer(f as i64)
+            }
+            seed => seed,
+        };
+        let state = match seed {
+            NumericValue::Integer(i) => i as u64,
+            NumericValue::Float(f) => f.to_bits(),
+        };
+        RandomNumbers {
+            seed: seed,
+            state: state,
+        }
+    }
+
+    /// Restarts the sequence from `seed`, returning the previous seed
+    pub(crate) fn reseed(&mut self, seed: NumericValue) -> NumericValue {
+        let previous_seed = self.seed;
+        *self = RandomNumbers::with_seed(seed);
+        previous_seed
+    }
+
+    /// A uniformly distributed number in [0, 1)
+    pub(crate) fn next(&mut self) -> f64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+
+        // The top 53 bits fill the mantissa of the float exactly
+        (z >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sequences_repeat_for_the_same_seed() {
+        let mut random = RandomNumbers::new();
+        let first: Vec<f64> = (0..5).map(|_| random.next()).collect();
+        assert!(first.iter().all(|n| *n >= 0.0 && *n < 1.0));
+
+        assert_eq!(
+            random.reseed(NumericValue::Integer(0)),
+            NumericValue::Integer(0)
+        );
+        let second: Vec<f64> = (0..5).map(|_| random.next()).collect();
+        assert_eq!(first, second);
+
+        assert_eq!(
+            random.reseed(NumericValue::Float(1.5)),
+            NumericValue::Integer(0)
+        );
+        assert_ne!(random.next(), first[0]);
+        assert_eq!(
+            random.reseed(NumericValue::Integer(2)),
+            NumericValue::Float(1.5)
+        );
+    }
+
+    #[test]
+    fn whole_numbers_seed_the_same_sequence_as_integers_or_floats() {
+        let mut random = RandomNumbers::new();
+        random.reseed(NumericValue::Integer(1));
+        let integer_seeded = random.next();
+
+        assert_eq!(
+            random.reseed(NumericValue::Float(1.0)),
+            NumericValue::Integer(1)
+        );
+        assert_eq!(random.next(), integer_seeded);
+        assert_eq!(
+            random.reseed(NumericValue::Integer(0)),
+            NumericValue::Integer(1)
+        );
+    }
+}
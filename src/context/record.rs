@@ -0,0 +1,88 @@
+use crate::{context::FieldSeparator, value::Value};
+
+/// The current input record, owned so that both `$0` and individual fields can be assigned
+pub(super) struct Record {
+    full_line: String,
+    fields: Vec<String>,
+}
+
+impl Record {
+    pub(super) fn new(line: &str, separator: &FieldSeparator) -> Record {
+        Record {
+            full_line: line.to_string(),
+            fields: separator
+                .split(line)
+                .iter()
+                .map(|field| field.to_string())
+                .collect(),
+        }
+    }
+
+    pub(super) fn field_count(&self) -> usize {
+        self.fields.len()
+    }
+
+    pub(super) fn fetch_field(&self, index: usize) -> Value {
+        match index {
+            0 => Value::from_input(self.full_line.clone()),
+            i => self
+                .fields
+                .get(i - 1)
+                .map(|field| Value::from_input(field.clone()))
+                .unwrap_or(Value::Uninitialized),
+        }
+    }
+
+    /// Assigning past the last field adds empty fields in between
+    pub(super) fn assign_field(&mut self, index: usize, value: String, output_separator: &str) {
+        if index > self.fields.len() {
+            self.fields.resize(index, String::new());
+        }
+        self.fields[index - 1] = value;
+        self.rebuild(output_separator);
+    }
+
+    pub(super) fn set_field_count(&mut self, count: usize, output_separator: &str) {
+        self.fields.resize(count, String::new());
+        self.rebuild(output_separator);
+    }
+
+    fn rebuild(&mut self, output_separator: &str) {
+        self.full_line = self.fields.join(output_separator);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn assigning_fields_rebuilds_the_line() {
+        let mut record = Record::new("  a b   c ", &FieldSeparator::Character(' '));
+        assert_eq!(record.field_count(), 3);
+        assert_eq!(
+            record.fetch_field(0),
+            Value::String("  a b   c ".to_string())
+        );
+
+        record.assign_field(2, "x".to_string(), "-");
+        assert_eq!(record.fetch_field(0), Value::String("a-x-c".to_string()));
+
+        record.assign_field(5, "e".to_string(), "-");
+        assert_eq!(record.field_count(), 5);
+        assert_eq!(record.fetch_field(0), Value::String("a-x-c--e".to_string()));
+        assert_eq!(record.fetch_field(4), Value::String("".to_string()));
+        assert_eq!(record.fetch_field(6), Value::Uninitialized);
+    }
+
+    #[test]
+    fn changing_the_field_count_truncates_or_pads() {
+        let mut record = Record::new("a,b,c", &FieldSeparator::Character(','));
+        record.set_field_count(2, ",");
+        assert_eq!(record.fetch_field(0), Value::String("a,b".to_string()));
+        assert_eq!(record.fetch_field(3), Value::Uninitialized);
+
+        record.set_field_count(4, ",");
+        assert_eq!(record.fetch_field(0), Value::String("a,b,,".to_string()));
+    }
+}
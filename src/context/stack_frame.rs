@@ -1,9 +1,20 @@
+use std::cell::RefCell;
 use std::collections::HashMap;
+use std::rc::Rc;
 
 use crate::value::Value;
 
+/// Arrays are shared so that they can be passed by reference into function calls
+pub(crate) type Array = Rc<RefCell<HashMap<String, Value>>>;
+
+#[derive(Clone)]
+pub(crate) enum Variable {
+    Scalar(Value),
+    Array(Array),
+}
+
 pub(crate) struct StackFrame {
-    variables: HashMap<String, Value>,
+    variables: HashMap<String, Variable>,
 }
 
 impl StackFrame {
@@ -13,11 +24,61 @@ impl StackFrame {
         }
     }
 
+    pub(super) fn contains(&self, variable_name: &str) -> bool {
+        self.variables.contains_key(variable_name)
+    }
+
+    pub(crate) fn fetch_binding(&self, variable_name: &str) -> Option<Variable> {
+        self.variables.get(variable_name).cloned()
+    }
+
     pub(super) fn fetch_variable(&self, variable_name: &str) -> Option<Value> {
-        self.variables.get(variable_name).map(|val| val.clone())
+        self.variables
+            .get(variable_name)
+            .map(|variable| match variable {
+                Variable::Scalar(value) => value.clone(),
+                Variable::Array(_) => {
+                    panic!("can't use array {} in a scalar context", variable_name)
+                }
+            })
     }
 
     pub(crate) fn assign_variable(&mut self, variable_name: &str, value: Value) {
-        self.variables.insert(variable_name.to_string(), value);
+        if let Some(Variable::Array(_)) = self.variables.get(variable_name) {
+            panic!("can't assign to {}; it's an array name", variable_name);
+        }
+        self.variables
+            .insert(variable_name.to_string(), Variable::Scalar(value));
+    }
+
+    pub(crate) fn bind_array(&mut self, variable_name: &str, array: Array) {
+        self.variables
+            .insert(variable_name.to_string(), Variable::Array(array));
+    }
+
+    pub(super) fn fetch_array(&self, variable_name: &str) -> Option<Array> {
+        match self.variables.get(variable_name) {
+            Some(Variable::Array(array)) => Some(array.clone()),
+            Some(Variable::Scalar(Value::Uninitialized)) | None => None,
+            Some(Variable::Scalar(_)) => {
+                panic!("can't use scalar {} as an array", variable_name)
+            }
+        }
+    }
+
+    /// Arrays are created on first use, and an uninitialized scalar (e.g. an unused function
+    /// parameter) becomes an array the first time it is subscripted
+    pub(super) fn fetch_array_mut(&mut self, variable_name: &str) -> Array {
+        let variable = self
+            .variables
+            .entry(variable_name.to_string())
+            .or_insert_with(|| Variable::Array(Array::default()));
+        if let Variable::Scalar(Value::Uninitialized) = variable {
+            *variable = Variable::Array(Array::default());
+        }
+        match variable {
+            Variable::Array(array) => array.clone(),
+            Variable::Scalar(_) => panic!("can't use scalar {} as an array", variable_name),
+        }
     }
 }
@@ -1,25 +1,64 @@
 use regex;
 
-use crate::context::{stack_frame::StackFrame, Record, VariableStore};
+use crate::context::{
+    input_streams::InputStreams,
+    output_streams::OutputStreams,
+    random::RandomNumbers,
+    record::Record,
+    stack_frame::{Array, StackFrame, Variable},
+    VariableStore,
+};
+use crate::runtime_error::compile_regex;
 use crate::value::{NumericValue, Value, UNINITIALIZED_VALUE};
 
-enum FieldSeparator {
+pub(crate) enum FieldSeparator {
     Character(char),
     Regex(regex::Regex),
 }
 
+impl FieldSeparator {
+    /// A single space splits on runs of whitespace, any other single character is used
+    /// literally, and anything longer is a regular expression
+    pub(crate) fn new(separator: &str) -> FieldSeparator {
+        if separator.chars().count() == 1 {
+            FieldSeparator::Character(separator.chars().next().unwrap())
+        } else {
+            FieldSeparator::Regex(compile_regex(separator))
+        }
+    }
+
+    pub(crate) fn split<'a>(&self, text: &'a str) -> Vec<&'a str> {
+        if text.is_empty() {
+            return vec![];
+        }
+        match self {
+            FieldSeparator::Character(' ') => text.split_whitespace().collect(),
+            FieldSeparator::Character(c) => text.split(*c).collect(),
+            FieldSeparator::Regex(re) => re.split(text).collect(),
+        }
+    }
+}
+
 pub(crate) struct Variables {
     field_separator: FieldSeparator,
+    record: Record,
     pub(super) global_variables: StackFrame,
     pub(super) function_variables: Vec<StackFrame>,
+    pub(super) random_numbers: RandomNumbers,
+    pub(super) output_streams: OutputStreams,
+    pub(super) input_streams: InputStreams,
 }
 
 impl Variables {
     pub(crate) fn empty() -> Variables {
         let mut default_variables = Variables {
             field_separator: FieldSeparator::Character(' '),
+            record: Record::new("", &FieldSeparator::Character(' ')),
             global_variables: StackFrame::empty(),
             function_variables: vec![],
+            random_numbers: RandomNumbers::new(),
+            output_streams: OutputStreams::new(),
+            input_streams: InputStreams::new(),
         };
 
         // Assign all default variable values
@@ -28,30 +67,78 @@ impl Variables {
         default_variables.assign_variable("ORS", Value::String("\n".to_string()));
         default_variables.assign_variable("OFMT", Value::String("%.6g".to_string()));
         default_variables.assign_variable("CONVFMT", Value::String("%.6g".to_string()));
+        default_variables.assign_variable("SUBSEP", Value::String("\x1c".to_string()));
 
         default_variables
     }
 
     fn set_field_separator(&mut self, new_separator: &str) {
-        if new_separator.len() == 1 {
-            self.field_separator = FieldSeparator::Character(new_separator.chars().next().unwrap())
+        self.field_separator = FieldSeparator::new(new_separator);
+    }
+
+    pub(super) fn set_record(&mut self, line: &str) {
+        self.record = Record::new(line, &self.field_separator);
+        self.update_field_count();
+    }
+
+    pub(super) fn fetch_field(&self, index: usize) -> Value {
+        self.record.fetch_field(index)
+    }
+
+    pub(super) fn assign_field(&mut self, index: usize, value: String) {
+        if index == 0 {
+            self.set_record(&value);
         } else {
-            self.field_separator = FieldSeparator::Regex(regex::Regex::new(new_separator).unwrap())
+            let output_separator = self.fetch_variable("OFS").coerce_to_string();
+            self.record.assign_field(index, value, &output_separator);
+            self.update_field_count();
         }
     }
 
-    pub(super) fn record_for_line<'a>(&self, line: &'a str) -> Record<'a> {
-        let fields = match &self.field_separator {
-            FieldSeparator::Character(' ') => line.split_whitespace().collect(),
-            FieldSeparator::Character(c1) => line.split(|c2| *c1 == c2).collect(),
-            FieldSeparator::Regex(re) => re.split(line).collect(),
-        };
-        Record {
-            full_line: line,
-            fields: fields,
+    fn update_field_count(&mut self) {
+        let count = self.record.field_count() as i64;
+        self.global_variables
+            .assign_variable("NF", Value::Numeric(NumericValue::Integer(count)));
+    }
+
+    /// Flushes and closes every redirected stream, waiting for any commands to finish
+    pub(crate) fn close_streams(&mut self) {
+        self.output_streams.close_all();
+        self.input_streams.close_all();
+    }
+
+    pub(super) fn split_with_field_separator<'a>(&self, text: &'a str) -> Vec<&'a str> {
+        self.field_separator.split(text)
+    }
+
+    fn frame_for(&self, variable_name: &str) -> &StackFrame {
+        match self.function_variables.last() {
+            Some(frame) if frame.contains(variable_name) => frame,
+            _ => &self.global_variables,
         }
     }
 
+    fn frame_for_mut(&mut self, variable_name: &str) -> &mut StackFrame {
+        let is_local = self
+            .function_variables
+            .last()
+            .is_some_and(|frame| frame.contains(variable_name));
+        if is_local {
+            self.function_variables.last_mut().unwrap()
+        } else {
+            &mut self.global_variables
+        }
+    }
+
+    pub(crate) fn fetch_binding(&self, variable_name: &str) -> Option<Variable> {
+        self.frame_for(variable_name).fetch_binding(variable_name)
+    }
+
+    pub(crate) fn bind_array(&mut self, variable_name: &str, array: Array) {
+        self.frame_for_mut(variable_name)
+            .bind_array(variable_name, array);
+    }
+
     pub(crate) fn increment_variable(&mut self, variable_name: &str) {
         match self.fetch_variable(variable_name).coerce_to_numeric() {
             NumericValue::Integer(i) => {
@@ -76,7 +163,7 @@ impl VariableStore for Variables {
 
     fn assign_variable(&mut self, variable_name: &str, value: Value) {
         if let Some(frame) = self.function_variables.last_mut() {
-            if let Some(_) = frame.fetch_variable(variable_name) {
+            if frame.contains(variable_name) {
                 frame.assign_variable(variable_name, value);
                 return;
             }
@@ -85,6 +172,61 @@ impl VariableStore for Variables {
         if variable_name == "FS" {
             self.set_field_separator(&value.coerce_to_string());
         }
+        if variable_name == "NF" {
+            let count = match value.coerce_to_numeric() {
+                NumericValue::Integer(i) => i,
+                NumericValue::Float(f) => f as i64,
+            };
+            if count < 0 {
+                panic!("NF cannot be set to a negative value: {}", count);
+            }
+            let output_separator = self.fetch_variable("OFS").coerce_to_string();
+            self.record
+                .set_field_count(count as usize, &output_separator);
+        }
         self.global_variables.assign_variable(variable_name, value);
     }
+
+    fn fetch_array_element(&mut self, array_name: &str, key: &str) -> Value {
+        // Referencing an element that doesn't exist creates it
+        self.frame_for_mut(array_name)
+            .fetch_array_mut(array_name)
+            .borrow_mut()
+            .entry(key.to_string())
+            .or_insert_with(|| UNINITIALIZED_VALUE.clone())
+            .clone()
+    }
+
+    fn assign_array_element(&mut self, array_name: &str, key: &str, value: Value) {
+        self.frame_for_mut(array_name)
+            .fetch_array_mut(array_name)
+            .borrow_mut()
+            .insert(key.to_string(), value);
+    }
+
+    fn array_contains(&self, array_name: &str, key: &str) -> bool {
+        self.frame_for(array_name)
+            .fetch_array(array_name)
+            .is_some_and(|array| array.borrow().contains_key(key))
+    }
+
+    fn delete_array_element(&mut self, array_name: &str, key: &str) {
+        self.frame_for_mut(array_name)
+            .fetch_array_mut(array_name)
+            .borrow_mut()
+            .remove(key);
+    }
+
+    fn delete_array(&mut self, array_name: &str) {
+        self.frame_for_mut(array_name)
+            .fetch_array_mut(array_name)
+            .borrow_mut()
+            .clear();
+    }
+
+    fn array_keys(&self, array_name: &str) -> Vec<String> {
+        self.frame_for(array_name)
+            .fetch_array(array_name)
+            .map_or(vec![], |array| array.borrow().keys().cloned().collect())
+    }
 }
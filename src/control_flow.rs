@@ -0,0 +1,18 @@
+use crate::value::Value;
+
+/// How execution should proceed once a statement has finished evaluating.
+///
+/// Anything other than `Normal` unwinds through the enclosing statements until it reaches the
+/// construct that handles it: loops handle `Break` and `Continue`, function calls handle `Return`,
+/// and the program run handles `Next`, `NextFile`, and `Exit`.
+#[derive(PartialEq, Debug, Clone)]
+pub(crate) enum ControlFlow {
+    Normal,
+    Break,
+    Continue,
+    Next,
+    NextFile,
+    // `None` keeps whatever status an earlier `exit` set
+    Exit(Option<i32>),
+    Return(Value),
+}
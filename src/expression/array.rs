@@ -0,0 +1,271 @@
+use regex::Regex;
+
+use nom::{
+    character::complete::one_of,
+    combinator::map,
+    multi::many0,
+    sequence::{pair, preceded, tuple},
+    IResult,
+};
+
+use super::{
+    parse_expression, variable::parse_variable_name, Assign, AssignableExpression, Expression,
+    ExpressionParseResult, Target,
+};
+use crate::{
+    context::{MutableContext, VariableStore},
+    function::Functions,
+    parse_utilities::{blank, keyword, newline_opt},
+    value::{NumericValue, Value},
+};
+
+#[derive(Debug)]
+struct ArrayElement {
+    array_name: String,
+    subscripts: Vec<Box<dyn Expression>>,
+}
+
+impl Expression for ArrayElement {
+    fn regex<'a>(&'a self) -> Option<&'a Regex> {
+        None
+    }
+
+    fn evaluate(&self, functions: &Functions, context: &mut MutableContext) -> Value {
+        let key = evaluate_subscripts(&self.subscripts, functions, context);
+        context.fetch_array_element(&self.array_name, &key)
+    }
+
+    fn assignable<'a>(&'a self) -> Option<&'a dyn Assign> {
+        Some(self)
+    }
+}
+
+impl Assign for ArrayElement {
+    fn resolve(&self, functions: &Functions, context: &mut MutableContext) -> Target {
+        if functions.get(&self.array_name).is_some() {
+            panic!("can't assign to {}; it's a function", self.array_name);
+        }
+        Target::ArrayElement {
+            array_name: self.array_name.clone(),
+            key: evaluate_subscripts(&self.subscripts, functions, context),
+        }
+    }
+}
+
+impl AssignableExpression for ArrayElement {}
+
+#[derive(Debug)]
+struct ArrayMembership {
+    subscripts: Vec<Box<dyn Expression>>,
+    array_name: String,
+}
+
+impl Expression for ArrayMembership {
+    fn regex<'a>(&'a self) -> Option<&'a Regex> {
+        None
+    }
+
+    fn evaluate(&self, functions: &Functions, context: &mut MutableContext) -> Value {
+        let key = evaluate_subscripts(&self.subscripts, functions, context);
+        let int_value = if context.array_contains(&self.array_name, &key) {
+            1
+        } else {
+            0
+        };
+        Value::Numeric(NumericValue::Integer(int_value))
+    }
+}
+
+/// Multi-dimensional subscripts are joined with SUBSEP into a single key
+pub(crate) fn evaluate_subscripts(
+    subscripts: &Vec<Box<dyn Expression>>,
+    functions: &Functions,
+    context: &mut MutableContext,
+) -> String {
+    let keys: Vec<String> = subscripts
+        .iter()
+        .map(|subscript| {
+            let value = subscript.evaluate(functions, context);
+            context.coerce_to_string(&value)
+        })
+        .collect();
+    keys.join(&context.fetch_variable("SUBSEP").coerce_to_string())
+}
+
+/// Parses the `[expr, expr, ...]` portion of an array reference
+pub(crate) fn parse_subscripts(input: &str) -> IResult<&str, Vec<Box<dyn Expression>>> {
+    map(
+        tuple((
+            one_of("["),
+            blank,
+            parse_expression,
+            many0(preceded(
+                tuple((blank, one_of(","), newline_opt)),
+                parse_expression,
+            )),
+            blank,
+            one_of("]"),
+        )),
+        |(_, _, first, mut rest, _, _)| {
+            rest.insert(0, first);
+            rest
+        },
+    )(input)
+}
+
+pub(super) fn parse_array_element(input: &str) -> ExpressionParseResult {
+    map(parse_assignable_array_element, |element| {
+        element as Box<dyn Expression>
+    })(input)
+}
+
+pub(super) fn parse_assignable_array_element(
+    input: &str,
+) -> IResult<&str, Box<dyn AssignableExpression>> {
+    let (i, (name, subscripts)) = pair(parse_variable_name, parse_subscripts)(input)?;
+
+    Result::Ok((
+        i,
+        Box::new(ArrayElement {
+            array_name: name.to_string(),
+            subscripts: subscripts,
+        }),
+    ))
+}
+
+fn parse_in_array(input: &str) -> IResult<&str, &str> {
+    preceded(tuple((blank, keyword("in"), blank)), parse_variable_name)(input)
+}
+
+/// The ` in arr` which completes `(i, j) in arr`, once the parenthesized subscripts have been
+/// parsed
+pub(super) fn parse_grouped_membership(
+    subscripts: Vec<Box<dyn Expression>>,
+    input: &str,
+) -> ExpressionParseResult {
+    let (i, name) = parse_in_array(input)?;
+    Result::Ok((
+        i,
+        Box::new(ArrayMembership {
+            subscripts: subscripts,
+            array_name: name.to_string(),
+        }),
+    ))
+}
+
+pub(super) fn membership_parser<F>(next_parser: F) -> impl Fn(&str) -> ExpressionParseResult
+where
+    F: Fn(&str) -> ExpressionParseResult,
+{
+    move |input: &str| {
+        map(
+            pair(&next_parser, many0(parse_in_array)),
+            |(first, mut rest)| {
+                rest.drain(0..).fold(first, |inner, name| {
+                    Box::new(ArrayMembership {
+                        subscripts: vec![inner],
+                        array_name: name.to_string(),
+                    })
+                })
+            },
+        )(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parse_expression;
+    use super::*;
+    use crate::test_utilities::empty_functions_and_variables;
+
+    #[test]
+    fn array_elements_can_be_assigned() {
+        let (functions, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+
+        let result = parse_assignable_array_element(r#"arr["a"]"#);
+        assert!(result.is_ok());
+        let element = result.unwrap().1;
+        element.assign(
+            &functions,
+            &mut context,
+            Value::Numeric(NumericValue::Integer(3)),
+        );
+        assert_eq!(
+            element.evaluate(&functions, &mut context),
+            Value::Numeric(NumericValue::Integer(3)),
+        );
+        assert_eq!(
+            context.fetch_array_element("arr", "a"),
+            Value::Numeric(NumericValue::Integer(3)),
+        );
+    }
+
+    #[test]
+    fn incremented_elements_evaluate_their_subscripts_once() {
+        let (functions, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+
+        let result = parse_expression("arr[i++]++ + arr[i++]++");
+        assert!(result.is_ok());
+        assert_eq!(
+            result.unwrap().1.evaluate(&functions, &mut context),
+            Value::Numeric(NumericValue::Integer(0)),
+        );
+        assert_eq!(
+            context.fetch_variable("i"),
+            Value::Numeric(NumericValue::Integer(2)),
+        );
+        assert_eq!(
+            context.fetch_array_element("arr", "0"),
+            Value::Numeric(NumericValue::Integer(1)),
+        );
+        assert_eq!(
+            context.fetch_array_element("arr", "1"),
+            Value::Numeric(NumericValue::Integer(1)),
+        );
+        assert!(!context.array_contains("arr", "2"));
+    }
+
+    #[test]
+    fn referencing_an_element_creates_it() {
+        let (functions, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+
+        let result =
+            parse_expression(r#"("x" in arr) + ("x" in arr) + (arr["x"] == "") + ("x" in arr)"#);
+        assert!(result.is_ok());
+        assert_eq!(
+            result.unwrap().1.evaluate(&functions, &mut context),
+            Value::Numeric(NumericValue::Integer(2)),
+        );
+    }
+
+    #[test]
+    fn multidimensional_subscripts_use_subsep() {
+        let (functions, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+
+        let element = parse_assignable_array_element(r#"grid[1, "b"]"#).unwrap().1;
+        element.assign(&functions, &mut context, Value::String("found".to_string()));
+        assert_eq!(
+            context.fetch_array_element("grid", "1\x1cb"),
+            Value::String("found".to_string()),
+        );
+
+        let result = parse_expression(r#"(1, "b") in grid"#);
+        assert!(result.is_ok());
+        let (remaining, expression) = result.unwrap();
+        assert_eq!(remaining, "");
+        assert_eq!(
+            expression.evaluate(&functions, &mut context),
+            Value::Numeric(NumericValue::Integer(1)),
+        );
+
+        let result = parse_expression(r#"(1, "c") in grid"#);
+        assert_eq!(
+            result.unwrap().1.evaluate(&functions, &mut context),
+            Value::Numeric(NumericValue::Integer(0)),
+        );
+    }
+}
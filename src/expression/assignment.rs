@@ -0,0 +1,220 @@
+use regex::Regex;
+
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    combinator::{map, not, peek},
+    sequence::{terminated, tuple},
+};
+
+use super::{binary_math::Operator, Expression, ExpressionParseResult};
+use crate::{
+    context::MutableContext,
+    function::Functions,
+    parse_utilities::{blank, newline_opt},
+    value::Value,
+};
+
+/// `target = value`, or a compound assignment such as `target += value`, which evaluates to
+/// the value that was assigned
+#[derive(Debug)]
+struct Assignment {
+    // Checked to be assignable while parsing
+    target: Box<dyn Expression>,
+    // `None` for a plain `=`
+    operator: Option<Operator>,
+    value: Box<dyn Expression>,
+}
+
+impl Expression for Assignment {
+    fn regex<'a>(&'a self) -> Option<&'a Regex> {
+        None
+    }
+
+    fn evaluate(&self, functions: &Functions, context: &mut MutableContext) -> Value {
+        let value = self.value.evaluate(functions, context);
+        let target = self
+            .target
+            .assignable()
+            .unwrap()
+            .resolve(functions, context);
+        let new_value = match self.operator {
+            Some(operator) => operator.apply(&target.fetch(context), &value),
+            None => value,
+        };
+        target.assign(context, new_value.clone());
+        new_value
+    }
+}
+
+/// Assignment binds the loosest and groups to the right, so `a = b = 0` assigns to `b` first
+///
+/// The target is parsed like any other expression, and only becomes an assignment when it is
+/// assignable and followed by an assignment operator, so that it is never parsed twice.
+pub(super) fn assignment_parser<F, G>(
+    parse_value: F,
+    next_parser: G,
+) -> impl Fn(&str) -> ExpressionParseResult
+where
+    F: Fn(&str) -> ExpressionParseResult,
+    G: Fn(&str) -> ExpressionParseResult,
+{
+    move |input: &str| {
+        let parse_operator = alt((
+            map(tag("+="), |_| Some(Operator::Add)),
+            map(tag("-="), |_| Some(Operator::Subtract)),
+            map(tag("*="), |_| Some(Operator::Multiply)),
+            map(tag("/="), |_| Some(Operator::Divide)),
+            map(tag("%="), |_| Some(Operator::Modulo)),
+            map(alt((tag("^="), tag("**="))), |_| Some(Operator::Exponent)),
+            // A lone `=`, which mustn't be the start of `==`
+            map(terminated(tag("="), not(peek(tag("=")))), |_| None),
+        ));
+        let (i, target) = next_parser(input)?;
+        if target.assignable().is_none() {
+            return Result::Ok((i, target));
+        }
+        match tuple((blank, parse_operator, newline_opt, |i| parse_value(i)))(i) {
+            Ok((i, (_, operator, _, value))) => Result::Ok((
+                i,
+                Box::new(Assignment {
+                    target: target,
+                    operator: operator,
+                    value: value,
+                }),
+            )),
+            Err(nom::Err::Error(_)) => Result::Ok((i, target)),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parse_expression;
+    use super::*;
+    use crate::context::VariableStore;
+    use crate::function::parse_function;
+    use crate::test_utilities::empty_functions_and_variables;
+    use crate::value::NumericValue;
+
+    #[test]
+    fn assignments_yield_the_assigned_value() {
+        let (functions, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+
+        let result = parse_expression("a = b = 1 + 2");
+        assert!(result.is_ok());
+        let (remaining, expression) = result.unwrap();
+        assert_eq!(remaining, "");
+        assert_eq!(
+            expression.evaluate(&functions, &mut context),
+            Value::Numeric(NumericValue::Integer(3))
+        );
+        assert_eq!(
+            context.fetch_variable("a"),
+            Value::Numeric(NumericValue::Integer(3))
+        );
+        assert_eq!(
+            context.fetch_variable("b"),
+            Value::Numeric(NumericValue::Integer(3))
+        );
+
+        // Comparisons are not assignments
+        let expression = parse_expression("a == 4").unwrap().1;
+        assert_eq!(
+            expression.evaluate(&functions, &mut context),
+            Value::Numeric(NumericValue::Integer(0))
+        );
+    }
+
+    #[test]
+    fn assignments_from_function_calls() {
+        let (mut functions, mut variables) = empty_functions_and_variables();
+        let function = parse_function(r#"function hello(name) { return "hello " name }"#)
+            .unwrap()
+            .1;
+        functions.insert(function.name.clone(), function);
+        let mut context = MutableContext::for_variables(&mut variables);
+
+        let (remaining, expression) = parse_expression(r#"variable = hello("hi")"#).unwrap();
+        assert_eq!(remaining, "");
+        assert_eq!(
+            expression.evaluate(&functions, &mut context),
+            Value::String("hello hi".to_string())
+        );
+        assert_eq!(
+            context.fetch_variable("variable"),
+            Value::String("hello hi".to_string())
+        );
+    }
+
+    #[test]
+    fn compound_assignments_update_in_place() {
+        let (functions, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+        context.set_record_with_line("10 20");
+
+        for (text, expected) in vec![
+            ("n += 7", 7),
+            ("n -= 2", 5),
+            ("n *= 4", 20),
+            ("n /= 2", 10),
+            ("n %= 4", 2),
+            ("n ^= 3", 8),
+            ("arr[1] += 5", 5),
+            ("$2 -= 1", 19),
+        ] {
+            let expression = parse_expression(text).unwrap().1;
+            assert_eq!(
+                expression.evaluate(&functions, &mut context),
+                Value::Numeric(NumericValue::Integer(expected)),
+                "{}",
+                text
+            );
+        }
+        assert_eq!(
+            context.fetch_array_element("arr", "1").coerce_to_string(),
+            "5"
+        );
+        assert_eq!(context.fetch_field(0), Value::String("10 19".to_string()));
+
+        // Assignments can be used within other expressions
+        let expression = parse_expression("(n = n - 1) > 0").unwrap().1;
+        assert_eq!(
+            expression.evaluate(&functions, &mut context),
+            Value::Numeric(NumericValue::Integer(1))
+        );
+        assert_eq!(
+            context.fetch_variable("n"),
+            Value::Numeric(NumericValue::Integer(7))
+        );
+    }
+
+    #[test]
+    fn compound_assignment_targets_are_evaluated_once() {
+        let (functions, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+        context.set_record_with_line("1 2");
+
+        for text in vec!["i = 1", "arr[i++] += 5", "j = 1", "$(j++) += 5"] {
+            parse_expression(text)
+                .unwrap()
+                .1
+                .evaluate(&functions, &mut context);
+        }
+        assert_eq!(
+            context.fetch_variable("i"),
+            Value::Numeric(NumericValue::Integer(2))
+        );
+        assert_eq!(
+            context.fetch_array_element("arr", "1"),
+            Value::Numeric(NumericValue::Integer(5))
+        );
+        assert_eq!(
+            context.fetch_variable("j"),
+            Value::Numeric(NumericValue::Integer(2))
+        );
+        assert_eq!(context.fetch_field(0), Value::String("6 2".to_string()));
+    }
+}
@@ -3,16 +3,15 @@ use regex::Regex;
 use nom::{
     branch::alt,
     bytes::complete::tag,
-    character::complete::multispace0,
-    combinator::map,
-    sequence::{delimited, tuple},
+    combinator::{map, opt, verify},
+    sequence::{delimited, pair},
 };
 
 use super::{Expression, ExpressionParseResult};
 use crate::{
     context::MutableContext,
     function::Functions,
-    printable::Printable,
+    parse_utilities::blank,
     value::{NumericValue, Value},
 };
 
@@ -38,24 +37,20 @@ impl Expression for BinaryComparison {
         None
     }
 
-    fn evaluate(&self, functions: &Functions, context: &mut MutableContext) -> Printable<Value> {
-        let Printable {
-            value: left_value,
-            output: mut left_output,
-        } = self.left.evaluate(functions, context);
-        let Printable {
-            value: right_value,
-            output: mut right_output,
-        } = self.right.evaluate(functions, context);
-
-        let result = if let (Value::Numeric(left_number), Value::Numeric(right_number)) =
-            (&left_value, &right_value)
-        {
-            let (x, y) = match (left_number, right_number) {
-                (NumericValue::Integer(x), NumericValue::Integer(y)) => ((*x as f64), (*y as f64)),
-                (NumericValue::Integer(x), NumericValue::Float(y)) => ((*x as f64), *y),
-                (NumericValue::Float(x), NumericValue::Integer(y)) => (*x, (*y as f64)),
-                (NumericValue::Float(x), NumericValue::Float(y)) => (*x, *y),
+    fn evaluate(&self, functions: &Functions, context: &mut MutableContext) -> Value {
+        let left_value = self.left.evaluate(functions, context);
+        let right_value = self.right.evaluate(functions, context);
+
+        // Numbers, numeric strings from the input, and uninitialized values compare numerically
+        let result = if left_value.compares_as_number() && right_value.compares_as_number() {
+            let (x, y) = match (
+                left_value.coerce_to_numeric(),
+                right_value.coerce_to_numeric(),
+            ) {
+                (NumericValue::Integer(x), NumericValue::Integer(y)) => ((x as f64), (y as f64)),
+                (NumericValue::Integer(x), NumericValue::Float(y)) => ((x as f64), y),
+                (NumericValue::Float(x), NumericValue::Integer(y)) => (x, (y as f64)),
+                (NumericValue::Float(x), NumericValue::Float(y)) => (x, y),
             };
             match self.operator {
                 Operator::Less => x < y,
@@ -67,8 +62,8 @@ impl Expression for BinaryComparison {
             }
         } else {
             let (s1, s2) = (
-                left_value.coerce_to_string(),
-                right_value.coerce_to_string(),
+                context.coerce_to_string(&left_value),
+                context.coerce_to_string(&right_value),
             );
             match &self.operator {
                 Operator::Less => s1 < s2,
@@ -81,26 +76,16 @@ impl Expression for BinaryComparison {
         };
 
         let int_value = if result { 1 } else { 0 };
-        left_output.append(&mut right_output);
-        Printable {
-            value: Value::Numeric(NumericValue::Integer(int_value)),
-            output: left_output,
-        }
+        Value::Numeric(NumericValue::Integer(int_value))
     }
 }
 
-pub(super) fn comparison_parser<F>(next_parser: F) -> impl Fn(&str) -> ExpressionParseResult
-where
-    F: Fn(&str) -> ExpressionParseResult,
-{
-    move |input: &str| {
-        alt((definite_comparison_parser(|i| next_parser(i)), |i| {
-            next_parser(i)
-        }))(input)
-    }
-}
-
-fn definite_comparison_parser<F>(next_parser: F) -> impl Fn(&str) -> ExpressionParseResult
+/// Without `allow_greater_than`, a lone `>` is left unparsed so it can redirect output
+/// The left side is parsed only once, whether or not a comparison operator follows it
+pub(super) fn comparison_parser<F>(
+    next_parser: F,
+    allow_greater_than: bool,
+) -> impl Fn(&str) -> ExpressionParseResult
 where
     F: Fn(&str) -> ExpressionParseResult,
 {
@@ -111,40 +96,41 @@ where
             tag("=="),
             tag("!="),
             tag(">="),
-            tag(">"),
+            verify(tag(">"), |_: &str| allow_greater_than),
             tag("<"),
         ));
-        let parse_operator =
-            map(
-                delimited(multispace0, operators, multispace0),
-                |operator| match operator {
-                    "<" => Operator::Less,
-                    "<=" => Operator::LessEqual,
-                    "==" => Operator::Equal,
-                    "!=" => Operator::NotEqual,
-                    ">" => Operator::Greater,
-                    ">=" => Operator::GreaterEqual,
-                    _ => panic!("Unrecognized comparison character: {}", operator),
-                },
-            );
+        let parse_operator = map(
+            delimited(blank, operators, blank),
+            |operator| match operator {
+                "<" => Operator::Less,
+                "<=" => Operator::LessEqual,
+                "==" => Operator::Equal,
+                "!=" => Operator::NotEqual,
+                ">" => Operator::Greater,
+                ">=" => Operator::GreaterEqual,
+                _ => panic!("Unrecognized comparison character: {}", operator),
+            },
+        );
 
-        let (i, (left, operator, right)) =
-            tuple((|i| next_parser(i), parse_operator, |i| next_parser(i)))(input)?;
+        let (i, (left, comparison)) =
+            pair(&next_parser, opt(pair(parse_operator, &next_parser)))(input)?;
 
-        Result::Ok((
-            i,
-            Box::new(BinaryComparison {
+        let expression = match comparison {
+            Some((operator, right)) => Box::new(BinaryComparison {
                 left: left,
                 operator: operator,
                 right: right,
             }),
-        ))
+            None => left,
+        };
+        Result::Ok((i, expression))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::super::literal::*;
+    use super::super::parse_expression;
     use super::*;
     use crate::test_utilities::empty_functions_and_variables;
 
@@ -152,19 +138,19 @@ mod tests {
     fn test_comparing_numbers() {
         let (functions, mut variables) = empty_functions_and_variables();
         let mut context = MutableContext::for_variables(&mut variables);
-        let parser = comparison_parser(parse_literal);
+        let parser = comparison_parser(parse_literal, true);
 
         let result = parser("1 < 2");
         assert!(result.is_ok());
         assert_eq!(
-            result.unwrap().1.evaluate(&functions, &mut context).value,
+            result.unwrap().1.evaluate(&functions, &mut context),
             Value::Numeric(NumericValue::Integer(1)),
         );
 
         let result = parser("1 > 2");
         assert!(result.is_ok());
         assert_eq!(
-            result.unwrap().1.evaluate(&functions, &mut context).value,
+            result.unwrap().1.evaluate(&functions, &mut context),
             Value::Numeric(NumericValue::Integer(0)),
         );
     }
@@ -173,19 +159,19 @@ mod tests {
     fn test_comparing_strings() {
         let (functions, mut variables) = empty_functions_and_variables();
         let mut context = MutableContext::for_variables(&mut variables);
-        let parser = comparison_parser(parse_literal);
+        let parser = comparison_parser(parse_literal, true);
 
         let result = parser(r#""a" < "b""#);
         assert!(result.is_ok());
         assert_eq!(
-            result.unwrap().1.evaluate(&functions, &mut context).value,
+            result.unwrap().1.evaluate(&functions, &mut context),
             Value::Numeric(NumericValue::Integer(1)),
         );
 
         let result = parser(r#""A" <= "a""#);
         assert!(result.is_ok());
         assert_eq!(
-            result.unwrap().1.evaluate(&functions, &mut context).value,
+            result.unwrap().1.evaluate(&functions, &mut context),
             Value::Numeric(NumericValue::Integer(1)),
         );
     }
@@ -194,21 +180,49 @@ mod tests {
     fn test_comparing_numbers_and_strings() {
         let (functions, mut variables) = empty_functions_and_variables();
         let mut context = MutableContext::for_variables(&mut variables);
-        let parser = comparison_parser(parse_literal);
+        let parser = comparison_parser(parse_literal, true);
 
         // Numbers come before letters
         let result = parser(r#""a" < 1"#);
         assert!(result.is_ok());
         assert_eq!(
-            result.unwrap().1.evaluate(&functions, &mut context).value,
+            result.unwrap().1.evaluate(&functions, &mut context),
             Value::Numeric(NumericValue::Integer(0)),
         );
 
         let result = parser(r#""1" == 1"#);
         assert!(result.is_ok());
         assert_eq!(
-            result.unwrap().1.evaluate(&functions, &mut context).value,
+            result.unwrap().1.evaluate(&functions, &mut context),
             Value::Numeric(NumericValue::Integer(1)),
         );
     }
+
+    #[test]
+    fn numeric_looking_input_compares_as_numbers() {
+        let (functions, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+        context.set_record_with_line("9 10.0 abc 010");
+
+        for (text, expected) in vec![
+            ("$1 < 10", 1),
+            ("$1 < $2", 1),
+            ("$2 == 10", 1),
+            ("$4 == $2", 1),
+            // A string constant on either side makes it a string comparison
+            (r#"$1 < "10""#, 0),
+            ("$3 > 10", 1),
+            ("unset == 0", 1),
+            (r#"unset == """#, 1),
+        ] {
+            let result = parse_expression(text);
+            assert!(result.is_ok());
+            assert_eq!(
+                result.unwrap().1.evaluate(&functions, &mut context),
+                Value::Numeric(NumericValue::Integer(expected)),
+                "{}",
+                text
+            );
+        }
+    }
 }
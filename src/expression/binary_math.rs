@@ -1,59 +1,53 @@
 use regex::Regex;
+use std::convert::TryFrom;
 
 use nom::{
-    character::complete::{multispace0, one_of},
-    combinator::map,
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::one_of,
+    combinator::{map, opt},
     multi::many0,
-    sequence::{delimited, pair},
+    sequence::{delimited, pair, preceded},
 };
 
 use super::{Expression, ExpressionParseResult};
 use crate::{
     context::MutableContext,
     function::Functions,
-    printable::Printable,
+    parse_utilities::blank,
+    runtime_error::runtime_error,
     value::{NumericValue, Value},
 };
 
-#[derive(Debug)]
-enum Operator {
+#[derive(Clone, Copy, Debug)]
+pub(super) enum Operator {
     Add,
     Subtract,
     Multiply,
     Divide,
     Modulo,
-    // Exponent,
-}
-
-#[derive(Debug)]
-struct BinaryMath {
-    left: Box<dyn Expression>,
-    operator: Operator,
-    right: Box<dyn Expression>,
+    Exponent,
 }
 
-impl Expression for BinaryMath {
-    fn regex<'a>(&'a self) -> Option<&'a Regex> {
-        None
-    }
-
-    fn evaluate(&self, functions: &Functions, context: &mut MutableContext) -> Printable<Value> {
-        let Printable {
-            value: left_value,
-            output: mut left_output,
-        } = self.left.evaluate(functions, context);
-        let Printable {
-            value: right_value,
-            output: mut right_output,
-        } = self.right.evaluate(functions, context);
-
-        let value = match (
-            &self.operator,
+impl Operator {
+    /// Both operands are coerced to numbers first. Integers which would overflow fall back to
+    /// floating point, as they would in any other awk.
+    pub(super) fn apply(&self, left_value: &Value, right_value: &Value) -> Value {
+        let (left, right) = (
             left_value.coerce_to_numeric(),
             right_value.coerce_to_numeric(),
-        ) {
+        );
+        match (self, right) {
+            (Operator::Divide, NumericValue::Integer(0)) => runtime_error("division by zero"),
+            (Operator::Divide, NumericValue::Float(0.0)) => runtime_error("division by zero"),
+            (Operator::Modulo, NumericValue::Integer(0)) => runtime_error("division by zero in %"),
+            (Operator::Modulo, NumericValue::Float(0.0)) => runtime_error("division by zero in %"),
+            _ => {}
+        }
+
+        match (self, left, right) {
             (Operator::Add, NumericValue::Integer(x), NumericValue::Integer(y)) => {
-                Value::Numeric(NumericValue::Integer(x + y))
+                integer_or_float(x.checked_add(y), || (x as f64) + (y as f64))
             }
             (Operator::Add, NumericValue::Integer(x), NumericValue::Float(y)) => {
                 Value::Numeric(NumericValue::Float((x as f64) + y))
@@ -65,7 +59,7 @@ impl Expression for BinaryMath {
                 Value::Numeric(NumericValue::Float(x + y))
             }
             (Operator::Subtract, NumericValue::Integer(x), NumericValue::Integer(y)) => {
-                Value::Numeric(NumericValue::Integer(x - y))
+                integer_or_float(x.checked_sub(y), || (x as f64) - (y as f64))
             }
             (Operator::Subtract, NumericValue::Integer(x), NumericValue::Float(y)) => {
                 Value::Numeric(NumericValue::Float((x as f64) - y))
@@ -77,7 +71,7 @@ impl Expression for BinaryMath {
                 Value::Numeric(NumericValue::Float(x - y))
             }
             (Operator::Multiply, NumericValue::Integer(x), NumericValue::Integer(y)) => {
-                Value::Numeric(NumericValue::Integer(x * y))
+                integer_or_float(x.checked_mul(y), || (x as f64) * (y as f64))
             }
             (Operator::Multiply, NumericValue::Integer(x), NumericValue::Float(y)) => {
                 Value::Numeric(NumericValue::Float((x as f64) * y))
@@ -89,12 +83,12 @@ impl Expression for BinaryMath {
                 Value::Numeric(NumericValue::Float(x * y))
             }
             (Operator::Divide, NumericValue::Integer(x), NumericValue::Integer(y)) => {
-                if x % y == 0 {
-                    Value::Numeric(NumericValue::Integer(x / y))
-                } else {
-                    // When y does not divide x, Awk switches to floating point division
-                    Value::Numeric(NumericValue::Float((x as f64) / (y as f64)))
-                }
+                // When y does not divide x, Awk switches to floating point division
+                let quotient = match x.checked_rem(y) {
+                    Some(0) => x.checked_div(y),
+                    _ => None,
+                };
+                integer_or_float(quotient, || (x as f64) / (y as f64))
             }
             (Operator::Divide, NumericValue::Integer(x), NumericValue::Float(y)) => {
                 Value::Numeric(NumericValue::Float((x as f64) / y))
@@ -106,7 +100,7 @@ impl Expression for BinaryMath {
                 Value::Numeric(NumericValue::Float(x / y))
             }
             (Operator::Modulo, NumericValue::Integer(x), NumericValue::Integer(y)) => {
-                Value::Numeric(NumericValue::Integer(x % y))
+                integer_or_float(x.checked_rem(y), || (x as f64) % (y as f64))
             }
             (Operator::Modulo, NumericValue::Integer(x), NumericValue::Float(y)) => {
                 Value::Numeric(NumericValue::Float((x as f64) % y))
@@ -117,15 +111,51 @@ impl Expression for BinaryMath {
             (Operator::Modulo, NumericValue::Float(x), NumericValue::Float(y)) => {
                 Value::Numeric(NumericValue::Float(x % y))
             }
-        };
-        left_output.append(&mut right_output);
-        Printable {
-            value: value,
-            output: left_output,
+            (Operator::Exponent, NumericValue::Integer(x), NumericValue::Integer(y)) => {
+                // Whole number powers stay integers as long as they fit
+                integer_or_float(u32::try_from(y).ok().and_then(|y| x.checked_pow(y)), || {
+                    (x as f64).powf(y as f64)
+                })
+            }
+            (Operator::Exponent, NumericValue::Integer(x), NumericValue::Float(y)) => {
+                Value::Numeric(NumericValue::Float((x as f64).powf(y)))
+            }
+            (Operator::Exponent, NumericValue::Float(x), NumericValue::Integer(y)) => {
+                Value::Numeric(NumericValue::Float(x.powf(y as f64)))
+            }
+            (Operator::Exponent, NumericValue::Float(x), NumericValue::Float(y)) => {
+                Value::Numeric(NumericValue::Float(x.powf(y)))
+            }
         }
     }
 }
 
+fn integer_or_float<F: FnOnce() -> f64>(result: Option<i64>, as_float: F) -> Value {
+    match result {
+        Some(result) => Value::Numeric(NumericValue::Integer(result)),
+        None => Value::Numeric(NumericValue::Float(as_float())),
+    }
+}
+
+#[derive(Debug)]
+struct BinaryMath {
+    left: Box<dyn Expression>,
+    operator: Operator,
+    right: Box<dyn Expression>,
+}
+
+impl Expression for BinaryMath {
+    fn regex<'a>(&'a self) -> Option<&'a Regex> {
+        None
+    }
+
+    fn evaluate(&self, functions: &Functions, context: &mut MutableContext) -> Value {
+        let left_value = self.left.evaluate(functions, context);
+        let right_value = self.right.evaluate(functions, context);
+        self.operator.apply(&left_value, &right_value)
+    }
+}
+
 pub(super) fn addition_parser<F>(next_parser: F) -> impl Fn(&str) -> ExpressionParseResult
 where
     F: Fn(&str) -> ExpressionParseResult,
@@ -133,7 +163,7 @@ where
     move |input: &str| {
         let parse_added_expr = pair(
             map(
-                delimited(multispace0, one_of("+-"), multispace0),
+                delimited(blank, one_of("+-"), blank),
                 |operator_char| match operator_char {
                     '+' => Operator::Add,
                     '-' => Operator::Subtract,
@@ -158,6 +188,33 @@ where
     }
 }
 
+/// `^` and `**` group to the right, and bind tighter than a sign in front of them, so that
+/// `-2 ^ 2` is -4. A sign may follow the operator though, as in `2 ^ -1`, so the exponent is
+/// parsed by `parse_exponent`, which is expected to handle signs and further exponents.
+pub(super) fn exponent_parser<F, G>(
+    next_parser: F,
+    parse_exponent: G,
+) -> impl Fn(&str) -> ExpressionParseResult
+where
+    F: Fn(&str) -> ExpressionParseResult,
+    G: Fn(&str) -> ExpressionParseResult,
+{
+    move |input: &str| {
+        let parse_operator = delimited(blank, alt((tag("**"), tag("^"))), blank);
+        map(
+            pair(&next_parser, opt(preceded(parse_operator, &parse_exponent))),
+            |(base, exponent)| match exponent {
+                Some(exponent) => Box::new(BinaryMath {
+                    left: base,
+                    operator: Operator::Exponent,
+                    right: exponent,
+                }),
+                None => base,
+            },
+        )(input)
+    }
+}
+
 // Since multiplication is a higher precedence, it is lower level -> gets to consume characters
 // first
 pub(super) fn multiplication_parser<F>(next_parser: F) -> impl Fn(&str) -> ExpressionParseResult
@@ -167,7 +224,7 @@ where
     move |input: &str| {
         let parse_added_expr = pair(
             map(
-                delimited(multispace0, one_of("*/%"), multispace0),
+                delimited(blank, one_of("*/%"), blank),
                 |operator_char| match operator_char {
                     '*' => Operator::Multiply,
                     '/' => Operator::Divide,
@@ -196,7 +253,7 @@ where
 mod tests {
     use super::super::literal::*;
     use super::*;
-    use crate::test_utilities::empty_functions_and_variables;
+    use crate::test_utilities::{empty_functions_and_variables, evaluate};
 
     #[test]
     fn binary_expressions_can_evaluate() {
@@ -209,8 +266,7 @@ mod tests {
                 operator: Operator::Add,
                 right: Box::new(Literal::Numeric(NumericValue::Integer(3))),
             }
-            .evaluate(&functions, &mut context)
-            .value,
+            .evaluate(&functions, &mut context),
             Value::Numeric(NumericValue::Integer(5)),
         );
     }
@@ -224,21 +280,21 @@ mod tests {
         let result = parser("1 + 2 - 3 + 4 - 5.5");
         assert!(result.is_ok());
         assert_eq!(
-            result.unwrap().1.evaluate(&functions, &mut context).value,
+            result.unwrap().1.evaluate(&functions, &mut context),
             Value::Numeric(NumericValue::Float(-1.5)),
         );
 
         let result = parser("1 * 2 + 3 * 4");
         assert!(result.is_ok());
         assert_eq!(
-            result.unwrap().1.evaluate(&functions, &mut context).value,
+            result.unwrap().1.evaluate(&functions, &mut context),
             Value::Numeric(NumericValue::Integer(14)),
         );
 
         let result = parser("6 / 5 * 4 / 3");
         assert!(result.is_ok());
         assert_eq!(
-            result.unwrap().1.evaluate(&functions, &mut context).value,
+            result.unwrap().1.evaluate(&functions, &mut context),
             // Floating point error!
             Value::Numeric(NumericValue::Float(1.5999999999999999)),
         );
@@ -246,15 +302,51 @@ mod tests {
         let result = parser("6 / 3");
         assert!(result.is_ok());
         assert_eq!(
-            result.unwrap().1.evaluate(&functions, &mut context).value,
+            result.unwrap().1.evaluate(&functions, &mut context),
             Value::Numeric(NumericValue::Integer(2)),
         );
 
         let result = parser("6 % 5 * 4 / 3 % 2");
         assert!(result.is_ok());
         assert_eq!(
-            result.unwrap().1.evaluate(&functions, &mut context).value,
+            result.unwrap().1.evaluate(&functions, &mut context),
             Value::Numeric(NumericValue::Float(1.3333333333333333)),
         );
     }
+
+    #[test]
+    fn integer_overflow_falls_back_to_floating_point() {
+        let (_, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+
+        for (text, expected) in vec![
+            ("9223372036854775807 + 1", 9223372036854775808.0),
+            ("-9223372036854775807 - 2", -9223372036854775809.0),
+            ("4611686018427387904 * 4", 18446744073709551616.0),
+            ("1e300 * 1e300", f64::INFINITY),
+        ] {
+            assert_eq!(
+                evaluate(text, &mut context),
+                Value::Numeric(NumericValue::Float(expected)),
+                "{}",
+                text
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "division by zero")]
+    fn dividing_by_zero_is_an_error() {
+        let (_, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+        evaluate("1 / 0", &mut context);
+    }
+
+    #[test]
+    #[should_panic(expected = "division by zero in %")]
+    fn modulo_by_zero_is_an_error() {
+        let (_, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+        evaluate("1 % 0.0", &mut context);
+    }
 }
@@ -2,17 +2,16 @@ use regex::Regex;
 
 use nom::{
     bytes::complete::tag,
-    character::complete::{multispace0, one_of},
     combinator::map,
-    multi::{many0, many0_count},
-    sequence::{delimited, pair, preceded, terminated},
+    multi::many0,
+    sequence::{pair, preceded},
 };
 
 use super::{Expression, ExpressionParseResult};
 use crate::{
     context::MutableContext,
     function::Functions,
-    printable::Printable,
+    parse_utilities::{blank, newline_opt},
     value::{NumericValue, Value},
 };
 
@@ -34,15 +33,9 @@ impl Expression for BinaryBoolean {
         None
     }
 
-    fn evaluate(&self, functions: &Functions, context: &mut MutableContext) -> Printable<Value> {
-        let Printable {
-            value: left_value,
-            output: mut left_output,
-        } = self.left.evaluate(functions, context);
-        let Printable {
-            value: right_value,
-            output: mut right_output,
-        } = self.right.evaluate(functions, context);
+    fn evaluate(&self, functions: &Functions, context: &mut MutableContext) -> Value {
+        let left_value = self.left.evaluate(functions, context);
+        let right_value = self.right.evaluate(functions, context);
 
         let (left, right) = (
             left_value.coercion_to_boolean(),
@@ -54,58 +47,7 @@ impl Expression for BinaryBoolean {
             Operator::Or => left || right,
         };
         let int_value = if result { 1 } else { 0 };
-        left_output.append(&mut right_output);
-        Printable {
-            value: Value::Numeric(NumericValue::Integer(int_value)),
-            output: left_output,
-        }
-    }
-}
-
-#[derive(Debug)]
-struct NotBoolean {
-    expression: Box<dyn Expression>,
-}
-
-impl Expression for NotBoolean {
-    fn regex<'a>(&'a self) -> Option<&'a Regex> {
-        None
-    }
-
-    fn evaluate(&self, functions: &Functions, context: &mut MutableContext) -> Printable<Value> {
-        self.expression.evaluate(functions, context).map(|value| {
-            let int_value = if value.coercion_to_boolean() { 0 } else { 1 };
-            Value::Numeric(NumericValue::Integer(int_value))
-        })
-    }
-}
-
-pub(super) fn not_parser<F>(next_parser: F) -> impl Fn(&str) -> ExpressionParseResult
-where
-    F: Fn(&str) -> ExpressionParseResult,
-{
-    move |input: &str| {
-        let (i, (nestings, inner_expression)) =
-            pair(many0_count(terminated(one_of("!"), multispace0)), |i| {
-                next_parser(i)
-            })(input)?;
-
-        // Collapse nested negations for efficiency
-        let expression = if nestings == 0 {
-            inner_expression
-        } else if nestings % 2 == 1 {
-            Box::new(NotBoolean {
-                expression: inner_expression,
-            })
-        } else {
-            // Still need to coerce to {0,1} if the value was something else
-            Box::new(NotBoolean {
-                expression: Box::new(NotBoolean {
-                    expression: inner_expression,
-                }),
-            })
-        };
-        Result::Ok((i, expression))
+        Value::Numeric(NumericValue::Integer(int_value))
     }
 }
 
@@ -114,7 +56,7 @@ where
     F: Fn(&str) -> ExpressionParseResult,
 {
     move |input: &str| {
-        let parse_added_expr = preceded(delimited(multispace0, tag("||"), multispace0), |i| {
+        let parse_added_expr = preceded(pair(blank, pair(tag("||"), newline_opt)), |i| {
             next_parser(i)
         });
         map(
@@ -137,7 +79,7 @@ where
     F: Fn(&str) -> ExpressionParseResult,
 {
     move |input: &str| {
-        let parse_added_expr = preceded(delimited(multispace0, tag("&&"), multispace0), |i| {
+        let parse_added_expr = preceded(pair(blank, pair(tag("&&"), newline_opt)), |i| {
             next_parser(i)
         });
         map(
@@ -170,21 +112,21 @@ mod tests {
         let result = parser(r#""a" && 1"#);
         assert!(result.is_ok());
         assert_eq!(
-            result.unwrap().1.evaluate(&functions, &mut context).value,
+            result.unwrap().1.evaluate(&functions, &mut context),
             Value::Numeric(NumericValue::Integer(1)),
         );
 
         let result = parser(r#""a" && 0"#);
         assert!(result.is_ok());
         assert_eq!(
-            result.unwrap().1.evaluate(&functions, &mut context).value,
+            result.unwrap().1.evaluate(&functions, &mut context),
             Value::Numeric(NumericValue::Integer(0)),
         );
 
         let result = parser(r#""" && 1"#);
         assert!(result.is_ok());
         assert_eq!(
-            result.unwrap().1.evaluate(&functions, &mut context).value,
+            result.unwrap().1.evaluate(&functions, &mut context),
             Value::Numeric(NumericValue::Integer(0)),
         );
     }
@@ -198,92 +140,29 @@ mod tests {
         let result = parser(r#""a" || 1"#);
         assert!(result.is_ok());
         assert_eq!(
-            result.unwrap().1.evaluate(&functions, &mut context).value,
+            result.unwrap().1.evaluate(&functions, &mut context),
             Value::Numeric(NumericValue::Integer(1)),
         );
 
         let result = parser(r#""a" || 0"#);
         assert!(result.is_ok());
         assert_eq!(
-            result.unwrap().1.evaluate(&functions, &mut context).value,
+            result.unwrap().1.evaluate(&functions, &mut context),
             Value::Numeric(NumericValue::Integer(1)),
         );
 
         let result = parser(r#""" || 1"#);
         assert!(result.is_ok());
         assert_eq!(
-            result.unwrap().1.evaluate(&functions, &mut context).value,
+            result.unwrap().1.evaluate(&functions, &mut context),
             Value::Numeric(NumericValue::Integer(1)),
         );
 
         let result = parser(r#""" || 0"#);
         assert!(result.is_ok());
         assert_eq!(
-            result.unwrap().1.evaluate(&functions, &mut context).value,
-            Value::Numeric(NumericValue::Integer(0)),
-        );
-    }
-
-    #[test]
-    fn test_not_parsing() {
-        let (functions, mut variables) = empty_functions_and_variables();
-        let mut context = MutableContext::for_variables(&mut variables);
-        let parser = not_parser(parse_literal);
-
-        let result = parser(r#"!1"#);
-        assert!(result.is_ok());
-        assert_eq!(
-            result.unwrap().1.evaluate(&functions, &mut context).value,
+            result.unwrap().1.evaluate(&functions, &mut context),
             Value::Numeric(NumericValue::Integer(0)),
         );
-
-        let result = parser(r#"!0"#);
-        assert!(result.is_ok());
-        assert_eq!(
-            result.unwrap().1.evaluate(&functions, &mut context).value,
-            Value::Numeric(NumericValue::Integer(1)),
-        );
-
-        let result = parser(r#"!"a""#);
-        assert!(result.is_ok());
-        assert_eq!(
-            result.unwrap().1.evaluate(&functions, &mut context).value,
-            Value::Numeric(NumericValue::Integer(0)),
-        );
-
-        let result = parser(r#"!"""#);
-        assert!(result.is_ok());
-        assert_eq!(
-            result.unwrap().1.evaluate(&functions, &mut context).value,
-            Value::Numeric(NumericValue::Integer(1)),
-        );
-
-        let result = parser(r#"!!!!!0"#);
-        assert!(result.is_ok());
-        assert_eq!(
-            result.unwrap().1.evaluate(&functions, &mut context).value,
-            Value::Numeric(NumericValue::Integer(1)),
-        );
-    }
-
-    #[test]
-    fn test_iteration_compression() {
-        let (functions, mut variables) = empty_functions_and_variables();
-        let mut context = MutableContext::for_variables(&mut variables);
-        let parser = not_parser(parse_literal);
-
-        let result = parser(r#""abc""#);
-        assert!(result.is_ok());
-        assert_eq!(
-            result.unwrap().1.evaluate(&functions, &mut context).value,
-            Value::String("abc".to_string()),
-        );
-
-        let result = parser(r#"!!"abc""#);
-        assert!(result.is_ok());
-        assert_eq!(
-            result.unwrap().1.evaluate(&functions, &mut context).value,
-            Value::Numeric(NumericValue::Integer(1)),
-        );
     }
 }
@@ -0,0 +1,129 @@
+use std::process::{Command, Stdio};
+
+use super::{check_arity, evaluate_arguments, Expression};
+use crate::{
+    context::MutableContext,
+    function::Functions,
+    value::{NumericValue, Value},
+};
+
+/// Closes the file or command opened under the given name, returning -1 if it wasn't open
+pub(super) fn close(
+    arguments: &[Box<dyn Expression>],
+    functions: &Functions,
+    context: &mut MutableContext,
+) -> Value {
+    check_arity("close", arguments, 1, 1);
+    let value = arguments[0].evaluate(functions, context);
+    let name = context.coerce_to_string(&value);
+    Value::Numeric(NumericValue::Integer(context.close_stream(&name)))
+}
+
+/// Flushes the named stream, or every redirected stream when called without arguments
+pub(super) fn fflush(
+    arguments: &[Box<dyn Expression>],
+    functions: &Functions,
+    context: &mut MutableContext,
+) -> Value {
+    check_arity("fflush", arguments, 0, 1);
+    let values = evaluate_arguments(arguments, functions, context);
+    let result = match values.first() {
+        Some(value) => {
+            let name = context.coerce_to_string(value);
+            context.flush_stream(&name)
+        }
+        None => {
+            context.flush_output();
+            context.flush_all_streams()
+        }
+    };
+    Value::Numeric(NumericValue::Integer(result))
+}
+
+/// Runs a shell command and returns its exit status
+///
+/// Our output and the redirected streams are flushed first, so that the command's own output,
+/// which goes straight to our standard output, lands after everything printed before it.
+pub(super) fn system(
+    arguments: &[Box<dyn Expression>],
+    functions: &Functions,
+    context: &mut MutableContext,
+) -> Value {
+    check_arity("system", arguments, 1, 1);
+    let value = arguments[0].evaluate(functions, context);
+    let command = context.coerce_to_string(&value);
+    context.flush_output();
+    context.flush_all_streams();
+    let result = Command::new("sh")
+        .arg("-c")
+        .arg(&command)
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .status();
+    let status = match result {
+        Ok(status) => status.code().unwrap_or(-1) as i64,
+        Err(_) => -1,
+    };
+    Value::Numeric(NumericValue::Integer(status))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{File, OpenOptions};
+
+    use crate::{
+        context::MutableContext,
+        expression::parse_expression,
+        test_utilities::empty_functions_and_variables,
+        value::{NumericValue, Value},
+    };
+
+    #[test]
+    fn close_and_fflush_report_unopened_streams() {
+        let (functions, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+
+        for (text, expected) in vec![
+            (r#"close("not open")"#, -1),
+            (r#"fflush("not open")"#, -1),
+            ("fflush()", 0),
+        ] {
+            let expression = parse_expression(text).unwrap().1;
+            assert_eq!(
+                expression.evaluate(&functions, &mut context),
+                Value::Numeric(NumericValue::Integer(expected)),
+            );
+        }
+    }
+
+    #[test]
+    fn system_output_joins_the_output_in_order() {
+        let (functions, mut variables) = empty_functions_and_variables();
+        let path = std::env::temp_dir().join("rust_awk_system_output_test.txt");
+        File::create(&path).unwrap();
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        let mut context = MutableContext::for_variables(&mut variables).writing_to(&mut file);
+
+        // The command appends to the same file that our output is written to
+        let program = format!(
+            r#"system("echo hello >> {}; exit 2")"#,
+            path.to_str().unwrap()
+        );
+        context.write_output("before\n");
+        let expression = parse_expression(&program).unwrap().1;
+        assert_eq!(
+            expression.evaluate(&functions, &mut context),
+            Value::Numeric(NumericValue::Integer(2))
+        );
+        context.write_output("after\n");
+        context.flush_output();
+        drop(context);
+
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "before\nhello\nafter\n"
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,181 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::{check_arity, evaluate_arguments, Expression};
+use crate::{
+    context::MutableContext,
+    function::Functions,
+    value::{NumericValue, Value},
+};
+
+pub(super) fn int(
+    arguments: &[Box<dyn Expression>],
+    functions: &Functions,
+    context: &mut MutableContext,
+) -> Value {
+    check_arity("int", arguments, 1, 1);
+    let value = arguments[0].evaluate(functions, context);
+    // Truncates toward zero
+    let truncated = match value.coerce_to_numeric() {
+        NumericValue::Integer(i) => NumericValue::Integer(i),
+        NumericValue::Float(f) if f.abs() < i64::MAX as f64 => {
+            NumericValue::Integer(f.trunc() as i64)
+        }
+        NumericValue::Float(f) => NumericValue::Float(f.trunc()),
+    };
+    Value::Numeric(truncated)
+}
+
+pub(super) fn sqrt(
+    arguments: &[Box<dyn Expression>],
+    functions: &Functions,
+    context: &mut MutableContext,
+) -> Value {
+    apply("sqrt", f64::sqrt, arguments, functions, context)
+}
+
+pub(super) fn exp(
+    arguments: &[Box<dyn Expression>],
+    functions: &Functions,
+    context: &mut MutableContext,
+) -> Value {
+    apply("exp", f64::exp, arguments, functions, context)
+}
+
+pub(super) fn log(
+    arguments: &[Box<dyn Expression>],
+    functions: &Functions,
+    context: &mut MutableContext,
+) -> Value {
+    apply("log", f64::ln, arguments, functions, context)
+}
+
+pub(super) fn sin(
+    arguments: &[Box<dyn Expression>],
+    functions: &Functions,
+    context: &mut MutableContext,
+) -> Value {
+    apply("sin", f64::sin, arguments, functions, context)
+}
+
+pub(super) fn cos(
+    arguments: &[Box<dyn Expression>],
+    functions: &Functions,
+    context: &mut MutableContext,
+) -> Value {
+    apply("cos", f64::cos, arguments, functions, context)
+}
+
+pub(super) fn atan2(
+    arguments: &[Box<dyn Expression>],
+    functions: &Functions,
+    context: &mut MutableContext,
+) -> Value {
+    check_arity("atan2", arguments, 2, 2);
+    let values = evaluate_arguments(arguments, functions, context);
+    let (y, x) = (values[0].coerce_to_float(), values[1].coerce_to_float());
+    Value::Numeric(NumericValue::Float(y.atan2(x)))
+}
+
+pub(super) fn rand(
+    arguments: &[Box<dyn Expression>],
+    _functions: &Functions,
+    context: &mut MutableContext,
+) -> Value {
+    check_arity("rand", arguments, 0, 0);
+    Value::Numeric(NumericValue::Float(context.random_number()))
+}
+
+pub(super) fn srand(
+    arguments: &[Box<dyn Expression>],
+    functions: &Functions,
+    context: &mut MutableContext,
+) -> Value {
+    check_arity("srand", arguments, 0, 1);
+    let seed = match arguments.first() {
+        Some(argument) => argument.evaluate(functions, context).coerce_to_numeric(),
+        // Without an argument, the time of day is used as the seed
+        None => {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |duration| duration.as_secs());
+            NumericValue::Integer(now as i64)
+        }
+    };
+    Value::Numeric(context.seed_random_numbers(seed))
+}
+
+fn apply(
+    name: &str,
+    function: fn(f64) -> f64,
+    arguments: &[Box<dyn Expression>],
+    functions: &Functions,
+    context: &mut MutableContext,
+) -> Value {
+    check_arity(name, arguments, 1, 1);
+    let value = arguments[0].evaluate(functions, context);
+    Value::Numeric(NumericValue::Float(function(value.coerce_to_float())))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        context::MutableContext,
+        test_utilities::{empty_functions_and_variables, evaluate},
+        value::{NumericValue, Value},
+    };
+
+    #[test]
+    fn int_truncates_toward_zero() {
+        let (_, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+
+        assert_eq!(
+            evaluate("int(3.9)", &mut context),
+            Value::Numeric(NumericValue::Integer(3)),
+        );
+        assert_eq!(
+            evaluate(r#"int("-3.9abc")"#, &mut context),
+            Value::Numeric(NumericValue::Integer(-3)),
+        );
+    }
+
+    #[test]
+    fn math_functions_operate_on_floats() {
+        let (_, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+
+        assert_eq!(
+            evaluate("sqrt(16)", &mut context),
+            Value::Numeric(NumericValue::Float(4.0)),
+        );
+        assert_eq!(
+            evaluate("exp(0) + log(1) + cos(0) + sin(0)", &mut context),
+            Value::Numeric(NumericValue::Float(2.0)),
+        );
+        assert_eq!(
+            evaluate("atan2(0, -1)", &mut context),
+            Value::Numeric(NumericValue::Float(std::f64::consts::PI)),
+        );
+    }
+
+    #[test]
+    fn srand_makes_rand_reproducible() {
+        let (_, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+
+        assert_eq!(
+            evaluate("srand(42)", &mut context),
+            Value::Numeric(NumericValue::Integer(0)),
+        );
+        let first = evaluate("rand()", &mut context);
+        let second = evaluate("rand()", &mut context);
+        assert_ne!(first, second);
+
+        assert_eq!(
+            evaluate("srand(42)", &mut context),
+            Value::Numeric(NumericValue::Integer(42)),
+        );
+        assert_eq!(evaluate("rand()", &mut context), first);
+        assert_eq!(evaluate("rand()", &mut context), second);
+    }
+}
@@ -0,0 +1,83 @@
+use regex::Regex;
+
+use super::Expression;
+use crate::{
+    context::MutableContext, function::Functions, runtime_error::compile_regex, value::Value,
+};
+
+mod io;
+mod math;
+mod string;
+
+/// Built-in functions receive their unevaluated arguments, since some of them need to assign
+/// through an argument or treat it as an array rather than as a value
+pub(super) type Builtin = fn(&[Box<dyn Expression>], &Functions, &mut MutableContext) -> Value;
+
+pub(super) fn lookup(name: &str) -> Option<Builtin> {
+    match name {
+        // Arithmetic
+        "atan2" => Some(math::atan2),
+        "cos" => Some(math::cos),
+        "exp" => Some(math::exp),
+        "int" => Some(math::int),
+        "log" => Some(math::log),
+        "rand" => Some(math::rand),
+        "sin" => Some(math::sin),
+        "sqrt" => Some(math::sqrt),
+        "srand" => Some(math::srand),
+        // Input and output
+        "close" => Some(io::close),
+        "fflush" => Some(io::fflush),
+        "system" => Some(io::system),
+        // Strings
+        "gsub" => Some(string::gsub),
+        "index" => Some(string::index),
+        "length" => Some(string::length),
+        "match" => Some(string::match_regex),
+        "split" => Some(string::split),
+        "sprintf" => Some(string::sprintf),
+        "sub" => Some(string::sub),
+        "substr" => Some(string::substr),
+        "tolower" => Some(string::tolower),
+        "toupper" => Some(string::toupper),
+        _ => None,
+    }
+}
+
+fn check_arity(name: &str, arguments: &[Box<dyn Expression>], min: usize, max: usize) {
+    if arguments.len() < min || arguments.len() > max {
+        panic!(
+            "function {} called with {} args, accepts between {} and {}",
+            name,
+            arguments.len(),
+            min,
+            max
+        );
+    }
+}
+
+fn evaluate_arguments(
+    arguments: &[Box<dyn Expression>],
+    functions: &Functions,
+    context: &mut MutableContext,
+) -> Vec<Value> {
+    arguments
+        .iter()
+        .map(|argument| argument.evaluate(functions, context))
+        .collect()
+}
+
+/// Regex literals are used directly, while any other expression is compiled from its value
+fn evaluate_regex(
+    argument: &dyn Expression,
+    functions: &Functions,
+    context: &mut MutableContext,
+) -> Regex {
+    match argument.regex() {
+        Some(regex) => regex.clone(),
+        None => {
+            let value = argument.evaluate(functions, context);
+            compile_regex(&context.coerce_to_string(&value))
+        }
+    }
+}
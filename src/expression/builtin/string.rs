@@ -0,0 +1,460 @@
+use regex::Regex;
+
+use super::{check_arity, evaluate_arguments, evaluate_regex, Expression};
+use crate::{
+    context::{stack_frame::Variable, FieldSeparator, MutableContext, VariableStore},
+    expression::Target,
+    function::Functions,
+    value::{NumericValue, Value},
+};
+
+pub(super) fn length(
+    arguments: &[Box<dyn Expression>],
+    functions: &Functions,
+    context: &mut MutableContext,
+) -> Value {
+    check_arity("length", arguments, 0, 1);
+
+    // Arrays report their number of elements
+    let array_length = arguments
+        .first()
+        .and_then(|argument| argument.variable_name())
+        .and_then(|name| match context.fetch_binding(name) {
+            Some(Variable::Array(array)) => Some(array.borrow().len()),
+            _ => None,
+        });
+    if let Some(array_length) = array_length {
+        return Value::Numeric(NumericValue::Integer(array_length as i64));
+    }
+
+    let value = match arguments.first() {
+        Some(argument) => argument.evaluate(functions, context),
+        None => context.fetch_field(0),
+    };
+    let length = context.coerce_to_string(&value).chars().count();
+    Value::Numeric(NumericValue::Integer(length as i64))
+}
+
+pub(super) fn substr(
+    arguments: &[Box<dyn Expression>],
+    functions: &Functions,
+    context: &mut MutableContext,
+) -> Value {
+    check_arity("substr", arguments, 2, 3);
+    let values = evaluate_arguments(arguments, functions, context);
+    let string = context.coerce_to_string(&values[0]);
+
+    // Positions are 1-based, and anything outside of the string is silently dropped
+    let start = values[1].coerce_to_float().round();
+    let end = match values.get(2) {
+        Some(length) => start + length.coerce_to_float().round(),
+        None => f64::INFINITY,
+    };
+    let first = start.max(1.0);
+    let last = end.min(string.chars().count() as f64 + 1.0);
+    if last <= first {
+        return Value::String("".to_string());
+    }
+    Value::String(
+        string
+            .chars()
+            .skip(first as usize - 1)
+            .take((last - first) as usize)
+            .collect(),
+    )
+}
+
+pub(super) fn index(
+    arguments: &[Box<dyn Expression>],
+    functions: &Functions,
+    context: &mut MutableContext,
+) -> Value {
+    check_arity("index", arguments, 2, 2);
+    let values = evaluate_arguments(arguments, functions, context);
+    let string = context.coerce_to_string(&values[0]);
+    let target = context.coerce_to_string(&values[1]);
+    let position = match string.find(&target) {
+        Some(offset) => string[..offset].chars().count() as i64 + 1,
+        None => 0,
+    };
+    Value::Numeric(NumericValue::Integer(position))
+}
+
+pub(super) fn split(
+    arguments: &[Box<dyn Expression>],
+    functions: &Functions,
+    context: &mut MutableContext,
+) -> Value {
+    check_arity("split", arguments, 2, 3);
+    let array_name = match arguments[1].variable_name() {
+        Some(name) => name,
+        None => panic!("split requires an array name as its second argument"),
+    };
+
+    let value = arguments[0].evaluate(functions, context);
+    let separator = match arguments.get(2) {
+        None => None,
+        Some(argument) => match argument.regex() {
+            Some(regex) => Some(FieldSeparator::Regex(regex.clone())),
+            None => {
+                let separator = argument.evaluate(functions, context);
+                Some(FieldSeparator::new(&context.coerce_to_string(&separator)))
+            }
+        },
+    };
+    let string = context.coerce_to_string(&value);
+
+    // Without an explicit separator, FS is used just like for splitting records
+    let pieces: Vec<String> = match separator {
+        Some(separator) => separator.split(&string),
+        None => context.split_with_field_separator(&string),
+    }
+    .iter()
+    .map(|piece| piece.to_string())
+    .collect();
+
+    context.delete_array(array_name);
+    for (i, piece) in pieces.iter().enumerate() {
+        context.assign_array_element(
+            array_name,
+            &(i + 1).to_string(),
+            Value::from_input(piece.clone()),
+        );
+    }
+    Value::Numeric(NumericValue::Integer(pieces.len() as i64))
+}
+
+pub(super) fn sub(
+    arguments: &[Box<dyn Expression>],
+    functions: &Functions,
+    context: &mut MutableContext,
+) -> Value {
+    substitute("sub", false, arguments, functions, context)
+}
+
+pub(super) fn gsub(
+    arguments: &[Box<dyn Expression>],
+    functions: &Functions,
+    context: &mut MutableContext,
+) -> Value {
+    substitute("gsub", true, arguments, functions, context)
+}
+
+fn substitute(
+    name: &str,
+    global: bool,
+    arguments: &[Box<dyn Expression>],
+    functions: &Functions,
+    context: &mut MutableContext,
+) -> Value {
+    check_arity(name, arguments, 2, 3);
+    let regex = evaluate_regex(arguments[0].as_ref(), functions, context);
+    let replacement = arguments[1].evaluate(functions, context);
+    // The target is resolved once, so that it is read and written in the same place
+    let target = match arguments.get(2).map(|target| target.assignable()) {
+        Some(Some(assignable)) => assignable.resolve(functions, context),
+        Some(None) => panic!(
+            "{} can only replace within variables, array elements and fields",
+            name
+        ),
+        None => Target::Field(0),
+    };
+    let value = target.fetch(context);
+    let (result, count) = replace_matches(
+        &regex,
+        &context.coerce_to_string(&value),
+        &context.coerce_to_string(&replacement),
+        global,
+    );
+    // The target is only touched when something was actually replaced
+    if count > 0 {
+        target.assign(context, Value::String(result));
+    }
+    Value::Numeric(NumericValue::Integer(count))
+}
+
+fn replace_matches(regex: &Regex, string: &str, replacement: &str, global: bool) -> (String, i64) {
+    let mut result = String::new();
+    let mut count = 0;
+    let mut last_end = 0;
+    for matched in regex.find_iter(string) {
+        result.push_str(&string[last_end..matched.start()]);
+        result.push_str(&expand_replacement(replacement, matched.as_str()));
+        last_end = matched.end();
+        count += 1;
+        if !global {
+            break;
+        }
+    }
+    result.push_str(&string[last_end..]);
+    (result, count)
+}
+
+/// Within a replacement, `&` stands for the matched text while `\&` is a literal ampersand
+fn expand_replacement(replacement: &str, matched: &str) -> String {
+    let mut result = String::new();
+    let mut chars = replacement.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'&') || chars.peek() == Some(&'\\') => {
+                result.push(chars.next().unwrap())
+            }
+            '&' => result.push_str(matched),
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+pub(super) fn match_regex(
+    arguments: &[Box<dyn Expression>],
+    functions: &Functions,
+    context: &mut MutableContext,
+) -> Value {
+    check_arity("match", arguments, 2, 2);
+    let value = arguments[0].evaluate(functions, context);
+    let regex = evaluate_regex(arguments[1].as_ref(), functions, context);
+    let string = context.coerce_to_string(&value);
+    let (start, length) = match regex.find(&string) {
+        Some(matched) => (
+            string[..matched.start()].chars().count() as i64 + 1,
+            matched.as_str().chars().count() as i64,
+        ),
+        None => (0, -1),
+    };
+    context.assign_variable("RSTART", Value::Numeric(NumericValue::Integer(start)));
+    context.assign_variable("RLENGTH", Value::Numeric(NumericValue::Integer(length)));
+    Value::Numeric(NumericValue::Integer(start))
+}
+
+pub(super) fn tolower(
+    arguments: &[Box<dyn Expression>],
+    functions: &Functions,
+    context: &mut MutableContext,
+) -> Value {
+    check_arity("tolower", arguments, 1, 1);
+    let value = arguments[0].evaluate(functions, context);
+    Value::String(context.coerce_to_string(&value).to_lowercase())
+}
+
+pub(super) fn toupper(
+    arguments: &[Box<dyn Expression>],
+    functions: &Functions,
+    context: &mut MutableContext,
+) -> Value {
+    check_arity("toupper", arguments, 1, 1);
+    let value = arguments[0].evaluate(functions, context);
+    Value::String(context.coerce_to_string(&value).to_uppercase())
+}
+
+pub(super) fn sprintf(
+    arguments: &[Box<dyn Expression>],
+    functions: &Functions,
+    context: &mut MutableContext,
+) -> Value {
+    if arguments.is_empty() {
+        panic!("sprintf requires at least a format argument");
+    }
+    let values = evaluate_arguments(arguments, functions, context);
+    let format = context.coerce_to_string(&values[0]);
+    Value::String(context.sprintf(&format, &values[1..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        context::{MutableContext, VariableStore},
+        test_utilities::{empty_functions_and_variables, evaluate},
+        value::{NumericValue, Value},
+    };
+
+    fn string(s: &str) -> Value {
+        Value::String(s.to_string())
+    }
+
+    fn integer(i: i64) -> Value {
+        Value::Numeric(NumericValue::Integer(i))
+    }
+
+    #[test]
+    fn sprintf_formats_arguments() {
+        let (_, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+
+        assert_eq!(
+            evaluate(r#"sprintf("%05.1f%%", 12.345)"#, &mut context),
+            string("012.3%"),
+        );
+    }
+
+    #[test]
+    fn length_of_strings_records_and_arrays() {
+        let (_, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+        context.set_record_with_line("hello world");
+
+        assert_eq!(evaluate(r#"length("héllo")"#, &mut context), integer(5));
+        assert_eq!(evaluate("length(12345)", &mut context), integer(5));
+        assert_eq!(evaluate("length()", &mut context), integer(11));
+        assert_eq!(evaluate("length + 1", &mut context), integer(12));
+
+        context.assign_array_element("arr", "a", integer(1));
+        context.assign_array_element("arr", "b", integer(2));
+        assert_eq!(evaluate("length(arr)", &mut context), integer(2));
+    }
+
+    #[test]
+    fn substr_and_index_are_one_based() {
+        let (_, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+
+        assert_eq!(
+            evaluate(r#"substr("hello", 2)"#, &mut context),
+            string("ello")
+        );
+        assert_eq!(
+            evaluate(r#"substr("hello", 2, 3)"#, &mut context),
+            string("ell")
+        );
+        assert_eq!(
+            evaluate(r#"substr("hello", 0, 2)"#, &mut context),
+            string("h")
+        );
+        assert_eq!(
+            evaluate(r#"substr("hello", -1)"#, &mut context),
+            string("hello")
+        );
+        assert_eq!(
+            evaluate(r#"substr("hello", 4, 10)"#, &mut context),
+            string("lo")
+        );
+        assert_eq!(evaluate(r#"substr("hello", 9)"#, &mut context), string(""));
+        assert_eq!(
+            evaluate(r#"substr("hello", 1.5, 1.5)"#, &mut context),
+            string("el")
+        );
+
+        assert_eq!(
+            evaluate(r#"index("banana", "nan")"#, &mut context),
+            integer(3)
+        );
+        assert_eq!(
+            evaluate(r#"index("banana", "x")"#, &mut context),
+            integer(0)
+        );
+    }
+
+    #[test]
+    fn split_fills_an_array() {
+        let (_, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+
+        assert_eq!(
+            evaluate(r#"split("  a b   c ", parts)"#, &mut context),
+            integer(3)
+        );
+        assert_eq!(context.fetch_array_element("parts", "3"), string("c"));
+
+        // Splitting again replaces the previous contents
+        assert_eq!(
+            evaluate(r#"split("x,y", parts, ",")"#, &mut context),
+            integer(2)
+        );
+        assert_eq!(context.array_keys("parts").len(), 2);
+        assert_eq!(context.fetch_array_element("parts", "2"), string("y"));
+
+        assert_eq!(
+            evaluate(r#"split("1a22b3", parts, /[a-z]/)"#, &mut context),
+            integer(3)
+        );
+        // Pieces which look like numbers are numeric strings, just like fields
+        assert_eq!(
+            context.fetch_array_element("parts", "2"),
+            Value::StrNum("22".to_string())
+        );
+
+        assert_eq!(evaluate(r#"split("", parts)"#, &mut context), integer(0));
+    }
+
+    #[test]
+    fn sub_and_gsub_replace_in_place() {
+        let (_, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+        context.assign_variable("s", string("banana"));
+        context.assign_array_element("arr", "k", string("a.b.c"));
+
+        assert_eq!(evaluate(r#"sub(/an/, "[&]", s)"#, &mut context), integer(1));
+        assert_eq!(context.fetch_variable("s"), string("b[an]ana"));
+
+        assert_eq!(evaluate(r#"gsub(/a/, "\\&", s)"#, &mut context), integer(3));
+        assert_eq!(context.fetch_variable("s"), string("b[&n]&n&"));
+
+        assert_eq!(
+            evaluate(r#"gsub("\\.", "-", arr["k"])"#, &mut context),
+            integer(2)
+        );
+        assert_eq!(context.fetch_array_element("arr", "k"), string("a-b-c"));
+
+        assert_eq!(evaluate(r#"sub(/z/, "y", s)"#, &mut context), integer(0));
+        assert_eq!(context.fetch_variable("s"), string("b[&n]&n&"));
+    }
+
+    #[test]
+    fn substitution_targets_are_evaluated_once() {
+        let (_, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+        context.assign_variable("i", integer(1));
+        context.assign_array_element("arr", "1", string("x"));
+
+        assert_eq!(
+            evaluate(r#"sub(/x/, "y", arr[i++])"#, &mut context),
+            integer(1)
+        );
+        assert_eq!(context.fetch_variable("i"), integer(2));
+        assert_eq!(context.fetch_array_element("arr", "1"), string("y"));
+        assert!(!context.array_contains("arr", "2"));
+    }
+
+    #[test]
+    fn match_sets_rstart_and_rlength() {
+        let (_, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+
+        assert_eq!(
+            evaluate(r#"match("foobar", /o+b/)"#, &mut context),
+            integer(2)
+        );
+        assert_eq!(context.fetch_variable("RSTART"), integer(2));
+        assert_eq!(context.fetch_variable("RLENGTH"), integer(3));
+
+        assert_eq!(
+            evaluate(r#"match("foobar", "z")"#, &mut context),
+            integer(0)
+        );
+        assert_eq!(context.fetch_variable("RSTART"), integer(0));
+        assert_eq!(context.fetch_variable("RLENGTH"), integer(-1));
+    }
+
+    #[test]
+    fn case_conversion() {
+        let (_, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+
+        assert_eq!(
+            evaluate(r#"toupper("MiXed 1")"#, &mut context),
+            string("MIXED 1")
+        );
+        assert_eq!(
+            evaluate(r#"tolower("MiXed 1")"#, &mut context),
+            string("mixed 1")
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "regular expression compile failed")]
+    fn dynamic_regexes_which_do_not_compile_are_an_error() {
+        let (_, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+        evaluate(r#"match("a", "[")"#, &mut context);
+    }
+}
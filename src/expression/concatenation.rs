@@ -0,0 +1,93 @@
+use regex::Regex;
+
+use nom::{
+    character::complete::one_of,
+    combinator::{map, not, peek},
+    multi::many0,
+    sequence::{pair, preceded},
+};
+
+use super::{Expression, ExpressionParseResult};
+use crate::{context::MutableContext, function::Functions, parse_utilities::blank, value::Value};
+
+#[derive(Debug)]
+struct Concatenation {
+    left: Box<dyn Expression>,
+    right: Box<dyn Expression>,
+}
+
+impl Expression for Concatenation {
+    fn regex<'a>(&'a self) -> Option<&'a Regex> {
+        None
+    }
+
+    fn evaluate(&self, functions: &Functions, context: &mut MutableContext) -> Value {
+        let left_value = self.left.evaluate(functions, context);
+        let right_value = self.right.evaluate(functions, context);
+        let mut result = context.coerce_to_string(&left_value);
+        result.push_str(&context.coerce_to_string(&right_value));
+        Value::String(result)
+    }
+}
+
+/// Expressions written next to each other are joined together as strings
+///
+/// An operand may not start with a sign, so `a -1` is a subtraction, as in other awks.
+pub(super) fn concatenation_parser<F>(next_parser: F) -> impl Fn(&str) -> ExpressionParseResult
+where
+    F: Fn(&str) -> ExpressionParseResult,
+{
+    move |input: &str| {
+        let parse_operand = preceded(pair(blank, not(peek(one_of("+-")))), &next_parser);
+        map(pair(&next_parser, many0(parse_operand)), |(first, rest)| {
+            rest.into_iter().fold(first, |left, right| {
+                Box::new(Concatenation {
+                    left: left,
+                    right: right,
+                })
+            })
+        })(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parse_expression;
+    use super::*;
+    use crate::context::VariableStore;
+    use crate::test_utilities::empty_functions_and_variables;
+    use crate::value::NumericValue;
+
+    #[test]
+    fn adjacent_expressions_are_joined() {
+        let (functions, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+        context.set_record_with_line("a b");
+        context.assign_variable("x", Value::Numeric(NumericValue::Integer(5)));
+        context.assign_array_element("arr", "5", Value::Numeric(NumericValue::Integer(1)));
+
+        for (text, expected) in vec![
+            (r#"$1 "-" $2"#, Value::String("a-b".to_string())),
+            ("$1 $2", Value::String("ab".to_string())),
+            ("x 1 + 2", Value::String("53".to_string())),
+            ("x (1) 0.5", Value::String("510.5".to_string())),
+            ("x -1", Value::Numeric(NumericValue::Integer(4))),
+            ("x - 1", Value::Numeric(NumericValue::Integer(4))),
+            // The joined string is compared as a string
+            ("x 1 < 6", Value::Numeric(NumericValue::Integer(1))),
+            ("x in arr", Value::Numeric(NumericValue::Integer(1))),
+            ("x \"\" in arr", Value::Numeric(NumericValue::Integer(1))),
+        ] {
+            let result = parse_expression(text);
+            assert!(result.is_ok());
+            let (remaining, expression) = result.unwrap();
+            assert_eq!(remaining, "", "{}", text);
+            assert_eq!(
+                expression.evaluate(&functions, &mut context),
+                expected,
+                "{}",
+                text
+            );
+        }
+    }
+}
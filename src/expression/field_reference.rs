@@ -2,16 +2,19 @@ use regex::Regex;
 use std::fmt::Debug;
 
 use nom::{
-    character::complete::{multispace0, one_of},
+    character::complete::one_of,
     multi::many0,
-    sequence::{pair, terminated},
+    sequence::{pair, terminated, tuple},
+    IResult,
 };
 
-use super::{Expression, ExpressionParseResult};
+use super::{
+    parse_primary, Assign, AssignableExpression, Expression, ExpressionParseResult, Target,
+};
 use crate::{
     context::MutableContext,
     function::Functions,
-    printable::Printable,
+    parse_utilities::blank,
     value::{NumericValue, Value},
 };
 
@@ -25,26 +28,44 @@ impl Expression for FieldReference {
         None
     }
 
-    fn evaluate(&self, functions: &Functions, context: &mut MutableContext) -> Printable<Value> {
-        self.expression.evaluate(functions, context).map(|value| {
-            let unsafe_index = match value.coerce_to_numeric() {
-                NumericValue::Integer(i) => i,
-                NumericValue::Float(f) => f.floor() as i64,
-            };
-            context.fetch_field(unsafe_index)
-        })
+    fn evaluate(&self, functions: &Functions, context: &mut MutableContext) -> Value {
+        let index = self.evaluate_index(functions, context);
+        context.fetch_field(index)
+    }
+
+    fn assignable<'a>(&'a self) -> Option<&'a dyn Assign> {
+        Some(self)
+    }
+}
+
+impl FieldReference {
+    fn evaluate_index(&self, functions: &Functions, context: &mut MutableContext) -> i64 {
+        match self
+            .expression
+            .evaluate(functions, context)
+            .coerce_to_numeric()
+        {
+            NumericValue::Integer(i) => i,
+            NumericValue::Float(f) => f.floor() as i64,
+        }
     }
 }
 
+impl Assign for FieldReference {
+    fn resolve(&self, functions: &Functions, context: &mut MutableContext) -> Target {
+        Target::Field(self.evaluate_index(functions, context))
+    }
+}
+
+impl AssignableExpression for FieldReference {}
+
 pub(super) fn field_reference_parser<F>(next_parser: F) -> impl Fn(&str) -> ExpressionParseResult
 where
     F: Fn(&str) -> ExpressionParseResult,
 {
     move |input: &str| {
         let (i, (references, inner_expression)) =
-            pair(many0(terminated(one_of("$"), multispace0)), |i| {
-                next_parser(i)
-            })(input)?;
+            pair(many0(terminated(one_of("$"), blank)), &next_parser)(input)?;
         let expression = references.iter().fold(inner_expression, |inner, _| {
             Box::new(FieldReference { expression: inner })
         });
@@ -52,11 +73,25 @@ where
     }
 }
 
+pub(super) fn parse_assignable_field_reference(
+    input: &str,
+) -> IResult<&str, Box<dyn AssignableExpression>> {
+    let (i, (_, _, expression)) =
+        tuple((one_of("$"), blank, field_reference_parser(parse_primary)))(input)?;
+
+    Result::Ok((
+        i,
+        Box::new(FieldReference {
+            expression: expression,
+        }),
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::super::literal::*;
     use super::*;
-    use crate::test_utilities::empty_functions_and_variables;
+    use crate::{context::VariableStore, test_utilities::empty_functions_and_variables};
 
     #[test]
     fn field_reference_can_evaluate() {
@@ -68,8 +103,7 @@ mod tests {
             FieldReference {
                 expression: Box::new(Literal::Numeric(NumericValue::Integer(1)))
             }
-            .evaluate(&functions, &mut context)
-            .value,
+            .evaluate(&functions, &mut context),
             Value::String("first".to_string()),
         );
     }
@@ -84,24 +118,65 @@ mod tests {
         assert_eq!(result.is_ok(), true);
         let expression = result.unwrap().1;
         assert_eq!(
-            expression.evaluate(&functions, &mut context).value,
+            expression.evaluate(&functions, &mut context),
             Value::Uninitialized,
         );
 
         context.set_record_with_line("hello");
         assert_eq!(
-            expression.evaluate(&functions, &mut context).value,
+            expression.evaluate(&functions, &mut context),
             Value::String("hello".to_string()),
         );
 
         let result = parser("$     1");
         assert_eq!(result.is_ok(), true);
         assert_eq!(
-            result.unwrap().1.evaluate(&functions, &mut context).value,
+            result.unwrap().1.evaluate(&functions, &mut context),
             Value::String("hello".to_string()),
         );
     }
 
+    #[test]
+    fn fields_can_be_assigned() {
+        let (functions, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+        context.assign_variable("OFS", Value::String("-".to_string()));
+        context.set_record_with_line("a b c");
+
+        let result = parse_assignable_field_reference("$(1 + 1)");
+        assert!(result.is_ok());
+        let field = result.unwrap().1;
+        field.assign(&functions, &mut context, Value::String("x".to_string()));
+        assert_eq!(context.fetch_field(0), Value::String("a-x-c".to_string()));
+
+        let field = parse_assignable_field_reference("$5").unwrap().1;
+        field.assign(
+            &functions,
+            &mut context,
+            Value::Numeric(NumericValue::Integer(5)),
+        );
+        assert_eq!(
+            context.fetch_field(0),
+            Value::String("a-x-c--5".to_string())
+        );
+        assert_eq!(
+            context.fetch_variable("NF"),
+            Value::Numeric(NumericValue::Integer(5)),
+        );
+
+        let field = parse_assignable_field_reference("$0").unwrap().1;
+        field.assign(&functions, &mut context, Value::String("d e".to_string()));
+        assert_eq!(context.fetch_field(2), Value::String("e".to_string()));
+        assert_eq!(
+            context.fetch_variable("NF"),
+            Value::Numeric(NumericValue::Integer(2)),
+        );
+
+        context.assign_variable("NF", Value::Numeric(NumericValue::Integer(1)));
+        assert_eq!(context.fetch_field(0), Value::String("d".to_string()));
+        assert_eq!(context.fetch_field(2), Value::Uninitialized);
+    }
+
     // #[test]
     // fn test_nested_field_references() {
     //     let (functions, mut variables, mut record) = empty_functions_and_variables();
@@ -113,7 +188,7 @@ mod tests {
     //     assert!(result.is_ok(), true);
     //     let expression = result.unwrap().1;
     //     assert_eq!(
-    //         expression.evaluate(&functions, &mut context).value,
+    //         expression.evaluate(&functions, &mut context),
     //         Value::String("hello".to_string()),
     //     );
     // }
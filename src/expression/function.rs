@@ -2,14 +2,22 @@ use regex::Regex;
 use std::fmt::Debug;
 
 use nom::{
-    character::complete::{multispace0, one_of},
-    combinator::map,
+    branch::alt,
+    character::complete::one_of,
+    combinator::{map, opt},
     multi::many0,
     sequence::{pair, preceded, tuple},
 };
 
-use super::{parse_expression, variable::parse_variable_name, Expression, ExpressionParseResult};
-use crate::{context::MutableContext, function::Functions, printable::Printable, value::Value};
+use super::{
+    builtin, parse_expression, variable::parse_variable_name, Expression, ExpressionParseResult,
+};
+use crate::{
+    context::{stack_frame::Variable, MutableContext},
+    function::{Argument, Functions},
+    parse_utilities::{blank, keyword, newline_opt},
+    value::Value,
+};
 
 #[derive(Debug)]
 struct FunctionCall {
@@ -18,25 +26,36 @@ struct FunctionCall {
 }
 
 impl Expression for FunctionCall {
-    fn evaluate(&self, functions: &Functions, context: &mut MutableContext) -> Printable<Value> {
+    fn evaluate(&self, functions: &Functions, context: &mut MutableContext) -> Value {
+        if let Some(builtin) = builtin::lookup(&self.name) {
+            return builtin(&self.arguments, functions, context);
+        }
+
         let function = match functions.get(&self.name) {
             Some(func) => func,
             None => panic!("Could not find function with name {}", self.name),
         };
 
-        self.arguments
+        let arguments = self
+            .arguments
             .iter()
-            .fold(Printable::wrap(vec![]), |printable, argument| {
-                printable.and_then(|mut vec| {
-                    let Printable { value: v, output } = argument.evaluate(functions, context);
-                    vec.push(v);
-                    Printable {
-                        value: vec,
-                        output: output,
-                    }
-                })
+            .map(|argument| {
+                let bare_variable = argument
+                    .variable_name()
+                    .map(|name| (name, context.fetch_binding(name)));
+                match bare_variable {
+                    Some((_, Some(Variable::Array(array)))) => Argument::Array(array),
+                    Some((name, Some(Variable::Scalar(Value::Uninitialized))))
+                    | Some((name, None)) => Argument::Untyped(name.to_string()),
+                    _ => Argument::Scalar(argument.evaluate(functions, context)),
+                }
             })
-            .and_then(|values| function.invoke_with(values, functions, context))
+            .collect();
+        // An argument may have called a function which ended the record or the program
+        if context.is_interrupted() {
+            return Value::Uninitialized;
+        }
+        function.invoke_with(arguments, functions, context)
     }
 
     fn regex<'a>(&'a self) -> Option<&'a Regex> {
@@ -45,11 +64,15 @@ impl Expression for FunctionCall {
 }
 
 pub(crate) fn parse_function_call(input: &str) -> ExpressionParseResult {
+    alt((parse_call_with_arguments, parse_bare_length))(input)
+}
+
+fn parse_call_with_arguments(input: &str) -> ExpressionParseResult {
     let parse_arguments = map(
         pair(
             parse_expression,
             many0(preceded(
-                tuple((multispace0, one_of(","), multispace0)),
+                tuple((blank, one_of(","), newline_opt)),
                 parse_expression,
             )),
         ),
@@ -59,21 +82,34 @@ pub(crate) fn parse_function_call(input: &str) -> ExpressionParseResult {
         },
     );
 
-    let (i, (func_name, _, _, _, arguments, _, _)) = tuple((
-        parse_variable_name,
-        multispace0,
-        one_of("("),
-        multispace0,
-        parse_arguments,
-        multispace0,
-        one_of(")"),
-    ))(input)?;
+    // Only built-in functions allow a space before their arguments, since `name (x)` is
+    // otherwise the concatenation of a variable with `(x)`
+    let (i, func_name) = parse_variable_name(input)?;
+    let (i, _) = match builtin::lookup(func_name) {
+        Some(_) => blank(i)?,
+        None => (i, ""),
+    };
+    let (i, (_, _, arguments, _, _)) =
+        tuple((one_of("("), blank, opt(parse_arguments), blank, one_of(")")))(i)?;
 
     Result::Ok((
         i,
         Box::new(FunctionCall {
             name: func_name.to_string(),
-            arguments: arguments,
+            arguments: arguments.unwrap_or_default(),
+        }),
+    ))
+}
+
+/// `length` without parentheses is the length of the current record
+fn parse_bare_length(input: &str) -> ExpressionParseResult {
+    let (i, _) = keyword("length")(input)?;
+
+    Result::Ok((
+        i,
+        Box::new(FunctionCall {
+            name: "length".to_string(),
+            arguments: vec![],
         }),
     ))
 }
@@ -0,0 +1,229 @@
+use regex::Regex;
+
+use nom::{
+    bytes::complete::tag,
+    combinator::{map, opt},
+    multi::many0,
+    sequence::{pair, preceded, tuple},
+    IResult,
+};
+
+use super::{
+    field_reference::field_reference_parser, parse_assignable, parse_primary, AssignableExpression,
+    Expression, ExpressionParseResult,
+};
+use crate::{
+    context::MutableContext,
+    function::Functions,
+    parse_utilities::{blank, keyword},
+    value::{NumericValue, Value},
+};
+
+#[derive(Debug)]
+enum Source {
+    /// Plain `getline` reads the next record of the input being processed
+    CurrentInput,
+    /// `getline < file`
+    File(Box<dyn Expression>),
+    /// `command | getline`
+    Command(Box<dyn Expression>),
+}
+
+/// Evaluates to 1 when a line was read, 0 at the end of the input, and -1 if it couldn't be read
+#[derive(Debug)]
+struct Getline {
+    source: Source,
+    variable: Option<Box<dyn AssignableExpression>>,
+}
+
+impl Getline {
+    fn read_line(
+        &self,
+        name: Option<String>,
+        context: &mut MutableContext,
+    ) -> std::io::Result<Option<String>> {
+        match (&self.source, name) {
+            (Source::File(_), Some(name)) => context.read_from_file(&name),
+            (Source::Command(_), Some(name)) => context.read_from_command(&name),
+            _ => context.read_from_input(),
+        }
+    }
+
+    /// Reading into a variable leaves `$0` and `NF` alone, and only the current input and
+    /// commands count towards `NR`
+    fn store_line(&self, line: String, functions: &Functions, context: &mut MutableContext) {
+        match &self.variable {
+            Some(variable) => variable.assign(functions, context, Value::from_input(line)),
+            None => context.set_record_with_line(&line),
+        }
+        match self.source {
+            Source::CurrentInput => {
+                context.increment_variable("NR");
+                context.increment_variable("FNR");
+            }
+            Source::Command(_) => context.increment_variable("NR"),
+            Source::File(_) => {}
+        }
+    }
+}
+
+impl Expression for Getline {
+    fn evaluate(&self, functions: &Functions, context: &mut MutableContext) -> Value {
+        let name = match &self.source {
+            Source::CurrentInput => None,
+            Source::File(expression) | Source::Command(expression) => {
+                let value = expression.evaluate(functions, context);
+                Some(context.coerce_to_string(&value))
+            }
+        };
+
+        let result = match self.read_line(name, context) {
+            Ok(Some(line)) => {
+                self.store_line(line, functions, context);
+                1
+            }
+            Ok(None) => 0,
+            Err(_) => -1,
+        };
+        Value::Numeric(NumericValue::Integer(result))
+    }
+
+    fn regex<'a>(&'a self) -> Option<&'a Regex> {
+        None
+    }
+}
+
+fn parse_getline_variable(input: &str) -> IResult<&str, Box<dyn AssignableExpression>> {
+    preceded(blank, parse_assignable)(input)
+}
+
+/// `getline`, `getline var`, `getline < file`, and `getline var < file`
+pub(super) fn parse_simple_getline(input: &str) -> ExpressionParseResult {
+    let parse_file = preceded(
+        tuple((blank, tag("<"), blank)),
+        field_reference_parser(parse_primary),
+    );
+
+    map(
+        tuple((
+            keyword("getline"),
+            opt(parse_getline_variable),
+            opt(parse_file),
+        )),
+        |(_, variable, file)| {
+            Box::new(Getline {
+                source: match file {
+                    Some(file) => Source::File(file),
+                    None => Source::CurrentInput,
+                },
+                variable: variable,
+            }) as Box<dyn Expression>
+        },
+    )(input)
+}
+
+/// `command | getline` and `command | getline var`
+pub(super) fn getline_pipe_parser<F>(next_parser: F) -> impl Fn(&str) -> ExpressionParseResult
+where
+    F: Fn(&str) -> ExpressionParseResult,
+{
+    move |input: &str| {
+        let parse_pipe = preceded(
+            tuple((blank, tag("|"), blank, keyword("getline"))),
+            opt(parse_getline_variable),
+        );
+
+        map(
+            pair(&next_parser, many0(parse_pipe)),
+            |(command, variables)| {
+                variables.into_iter().fold(command, |command, variable| {
+                    Box::new(Getline {
+                        source: Source::Command(command),
+                        variable: variable,
+                    })
+                })
+            },
+        )(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parse_expression;
+    use super::*;
+    use crate::context::{MainInput, VariableStore};
+    use crate::test_utilities::empty_functions_and_variables;
+    use std::io::BufReader;
+
+    #[test]
+    fn getline_reads_from_the_current_input() {
+        let (functions, mut variables) = empty_functions_and_variables();
+        let mut input = MainInput::for_reader(Box::new(BufReader::new(
+            "second record\nthird\n".as_bytes(),
+        )));
+        let mut context = MutableContext::for_variables(&mut variables).reading_from(&mut input);
+        context.set_record_with_line("first");
+
+        let getline = parse_expression("getline").unwrap().1;
+        assert_eq!(
+            getline.evaluate(&functions, &mut context),
+            Value::Numeric(NumericValue::Integer(1))
+        );
+        assert_eq!(
+            context.fetch_field(0),
+            Value::String("second record".to_string())
+        );
+        assert_eq!(
+            context.fetch_variable("NF"),
+            Value::Numeric(NumericValue::Integer(2))
+        );
+
+        let getline = parse_expression("getline line").unwrap().1;
+        assert_eq!(
+            getline.evaluate(&functions, &mut context),
+            Value::Numeric(NumericValue::Integer(1))
+        );
+        assert_eq!(
+            context.fetch_variable("line"),
+            Value::String("third".to_string())
+        );
+        assert_eq!(
+            context.fetch_field(0),
+            Value::String("second record".to_string())
+        );
+        assert_eq!(
+            context.fetch_variable("NR"),
+            Value::Numeric(NumericValue::Integer(2))
+        );
+
+        assert_eq!(
+            getline.evaluate(&functions, &mut context),
+            Value::Numeric(NumericValue::Integer(0))
+        );
+    }
+
+    #[test]
+    fn getline_reads_from_commands_and_files() {
+        let (functions, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+
+        let getline = parse_expression(r#""echo a b c" | getline > 0"#).unwrap().1;
+        assert_eq!(
+            getline.evaluate(&functions, &mut context),
+            Value::Numeric(NumericValue::Integer(1))
+        );
+        assert_eq!(context.fetch_field(2), Value::String("b".to_string()));
+        assert_eq!(
+            context.fetch_variable("NR"),
+            Value::Numeric(NumericValue::Integer(1))
+        );
+
+        let getline = parse_expression(r#"getline line < "/nonexistent/rust-awk""#)
+            .unwrap()
+            .1;
+        assert_eq!(
+            getline.evaluate(&functions, &mut context),
+            Value::Numeric(NumericValue::Integer(-1))
+        );
+    }
+}
@@ -3,15 +3,14 @@ use regex::Regex;
 use nom::{
     branch::alt,
     bytes::complete::tag,
-    combinator::map,
-    sequence::{pair, tuple},
+    combinator::{map, verify},
+    sequence::pair,
 };
 
-use super::{AssignableExpression, Expression, ExpressionParseResult};
+use super::{Expression, ExpressionParseResult};
 use crate::{
     context::MutableContext,
     function::Functions,
-    printable::Printable,
     value::{NumericValue, Value},
 };
 
@@ -23,7 +22,8 @@ enum IncrementType {
 
 #[derive(Debug)]
 struct Increment {
-    variable: Box<dyn AssignableExpression>,
+    // Checked to be assignable while parsing
+    variable: Box<dyn Expression>,
     increment_type: IncrementType,
     is_increment: bool, // true for ++, false for --
 }
@@ -33,9 +33,14 @@ impl Expression for Increment {
         None
     }
 
-    fn evaluate(&self, functions: &Functions, context: &mut MutableContext) -> Printable<Value> {
-        // Get current value
-        let current_value = self.variable.evaluate(functions, context).value;
+    fn evaluate(&self, functions: &Functions, context: &mut MutableContext) -> Value {
+        // Get current value, evaluating any subscripts only once
+        let target = self
+            .variable
+            .assignable()
+            .unwrap()
+            .resolve(functions, context);
+        let current_value = target.fetch(context);
 
         // Calculate new value
         let new_value = match current_value.coerce_to_numeric() {
@@ -50,63 +55,60 @@ impl Expression for Increment {
         };
 
         // Assign new value
-        self.variable.assign(functions, context, new_value.clone());
+        target.assign(context, new_value.clone());
 
         // Return appropriate value based on prefix/postfix
         match self.increment_type {
-            IncrementType::Prefix => Printable::wrap(new_value),
-            IncrementType::Postfix => Printable::wrap(current_value),
+            IncrementType::Prefix => new_value,
+            // The old value is still converted to a number, so an unset `i++` gives 0
+            IncrementType::Postfix => Value::Numeric(current_value.coerce_to_numeric()),
         }
     }
 }
 
-fn parse_prefix_increment(input: &str) -> ExpressionParseResult {
-    map(
-        tuple((
-            alt((tag("++"), tag("--"))),
-            super::parse_assignable,
-        )),
-        |(op, variable)| {
-            Box::new(Increment {
-                variable: variable,
-                increment_type: IncrementType::Prefix,
-                is_increment: op == "++",
-            }) as Box<dyn Expression>
-        },
-    )(input)
-}
-
-fn parse_postfix_increment(input: &str) -> ExpressionParseResult {
-    map(
-        pair(
-            super::parse_assignable,
-            alt((tag("++"), tag("--"))),
-        ),
-        |(variable, op)| {
-            Box::new(Increment {
-                variable: variable,
-                increment_type: IncrementType::Postfix,
-                is_increment: op == "++",
-            }) as Box<dyn Expression>
-        },
-    )(input)
-}
-
-pub(super) fn increment_decrement_parser<F>(next_parser: F) -> impl Fn(&str) -> ExpressionParseResult
+/// `++x` and `x++`, along with their decrements
+///
+/// The operand is parsed only once, and only becomes an increment if it can be assigned to.
+pub(super) fn increment_decrement_parser<F>(
+    next_parser: F,
+) -> impl Fn(&str) -> ExpressionParseResult
 where
     F: Fn(&str) -> ExpressionParseResult,
 {
     move |input: &str| {
+        let parse_operator = || alt((map(tag("++"), |_| true), map(tag("--"), |_| false)));
+        let parse_variable = verify(&next_parser, |variable: &(dyn Expression + 'static)| {
+            variable.assignable().is_some()
+        });
+
         // Try prefix increment/decrement first
-        if let Ok(result) = parse_prefix_increment(input) {
-            return Ok(result);
+        if let Ok((i, (is_increment, variable))) = pair(parse_operator(), parse_variable)(input) {
+            return Ok((
+                i,
+                Box::new(Increment {
+                    variable: variable,
+                    increment_type: IncrementType::Prefix,
+                    is_increment: is_increment,
+                }),
+            ));
         }
-        // Try postfix increment/decrement next
-        if let Ok(result) = parse_postfix_increment(input) {
-            return Ok(result);
+
+        // Otherwise the operand may be followed by a postfix increment/decrement
+        let (i, variable) = next_parser(input)?;
+        if variable.assignable().is_none() {
+            return Ok((i, variable));
+        }
+        match parse_operator()(i) {
+            Ok((i, is_increment)) => Ok((
+                i,
+                Box::new(Increment {
+                    variable: variable,
+                    increment_type: IncrementType::Postfix,
+                    is_increment: is_increment,
+                }),
+            )),
+            Err(_) => Ok((i, variable)),
         }
-        // If neither worked, try the next parser
-        next_parser(input)
     }
 }
 
@@ -114,9 +116,9 @@ where
 mod tests {
     use super::super::parse_expression;
     use super::*;
+    use crate::context::VariableStore;
     use crate::test_utilities::empty_functions_and_variables;
     use crate::value::NumericValue;
-    use crate::context::VariableStore;
 
     #[test]
     fn test_prefix_increment() {
@@ -127,7 +129,7 @@ mod tests {
         let result = parse_expression("++x");
         assert!(result.is_ok());
         assert_eq!(
-            result.unwrap().1.evaluate(&functions, &mut context).value,
+            result.unwrap().1.evaluate(&functions, &mut context),
             Value::Numeric(NumericValue::Integer(6))
         );
         assert_eq!(
@@ -145,7 +147,7 @@ mod tests {
         let result = parse_expression("x++");
         assert!(result.is_ok());
         assert_eq!(
-            result.unwrap().1.evaluate(&functions, &mut context).value,
+            result.unwrap().1.evaluate(&functions, &mut context),
             Value::Numeric(NumericValue::Integer(5))
         );
         assert_eq!(
@@ -163,7 +165,7 @@ mod tests {
         let result = parse_expression("--x");
         assert!(result.is_ok());
         assert_eq!(
-            result.unwrap().1.evaluate(&functions, &mut context).value,
+            result.unwrap().1.evaluate(&functions, &mut context),
             Value::Numeric(NumericValue::Integer(4))
         );
         assert_eq!(
@@ -181,7 +183,7 @@ mod tests {
         let result = parse_expression("x--");
         assert!(result.is_ok());
         assert_eq!(
-            result.unwrap().1.evaluate(&functions, &mut context).value,
+            result.unwrap().1.evaluate(&functions, &mut context),
             Value::Numeric(NumericValue::Integer(5))
         );
         assert_eq!(
@@ -189,4 +191,4 @@ mod tests {
             Value::Numeric(NumericValue::Integer(4))
         );
     }
-} 
\ No newline at end of file
+}
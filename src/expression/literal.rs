@@ -1,16 +1,16 @@
 use nom::{
     branch::alt,
     character::complete::{none_of, one_of},
+    combinator::not,
     multi::many1,
     re_find,
-    sequence::{delimited, tuple},
+    sequence::{delimited, preceded, tuple},
     IResult,
 };
 
 use crate::{
     context::MutableContext,
     function::Functions,
-    printable::Printable,
     value::{parse_numeric, NumericValue, Value},
 };
 
@@ -32,8 +32,8 @@ impl Expression for Literal {
         }
     }
 
-    fn evaluate(&self, _functions: &Functions, _context: &mut MutableContext) -> Printable<Value> {
-        let value = match self {
+    fn evaluate(&self, _functions: &Functions, _context: &mut MutableContext) -> Value {
+        match self {
             Literal::String(string) => Value::String(string.clone()),
             Literal::Numeric(numeric) => Value::Numeric(numeric.clone()),
             Literal::Regex(_) => {
@@ -45,8 +45,7 @@ impl Expression for Literal {
                 // will be interpreted as such
                 Value::Uninitialized
             }
-        };
-        Printable::wrap(value)
+        }
     }
 }
 
@@ -58,8 +57,9 @@ pub(super) fn parse_literal(input: &str) -> ExpressionParseResult {
     ))(input)
 }
 
+/// Signs are unary operators rather than part of the literal, so that `-2 ^ 2` is -4
 fn parse_number_literal(input: &str) -> ExpressionParseResult {
-    let (i, number) = parse_numeric(input)?;
+    let (i, number) = preceded(not(one_of("+-")), parse_numeric)(input)?;
 
     Result::Ok((i, Box::new(Literal::Numeric(number))))
 }
@@ -67,7 +67,52 @@ fn parse_number_literal(input: &str) -> ExpressionParseResult {
 fn parse_string_literal(input: &str) -> ExpressionParseResult {
     let (i, contents) = delimited(one_of("\""), parse_string_contents, one_of("\""))(input)?;
 
-    Result::Ok((i, Box::new(Literal::String(contents.to_string()))))
+    Result::Ok((i, Box::new(Literal::String(unescape(contents)))))
+}
+
+/// Replaces the escape sequences awk recognizes within string literals
+fn unescape(contents: &str) -> String {
+    let mut result = String::new();
+    let mut chars = contents.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some('r') => result.push('\r'),
+            Some('a') => result.push('\x07'),
+            Some('b') => result.push('\x08'),
+            Some('f') => result.push('\x0c'),
+            Some('v') => result.push('\x0b'),
+            Some(escaped @ '\\') | Some(escaped @ '"') | Some(escaped @ '/') => {
+                result.push(escaped)
+            }
+            Some(digit @ '0'..='7') => {
+                // Octal escapes have at most three digits
+                let mut code = digit.to_digit(8).unwrap();
+                for _ in 0..2 {
+                    match chars.peek().and_then(|c| c.to_digit(8)) {
+                        Some(next_digit) => {
+                            code = code * 8 + next_digit;
+                            chars.next();
+                        }
+                        None => break,
+                    }
+                }
+                result.push(std::char::from_u32(code).unwrap());
+            }
+            // Unknown escapes are kept as-is
+            Some(other) => {
+                result.push('\\');
+                result.push(other);
+            }
+            None => result.push('\\'),
+        }
+    }
+    result
 }
 
 fn parse_string_contents(input: &str) -> IResult<&str, &str> {
@@ -107,12 +152,12 @@ mod tests {
 
         let string = Literal::String("hello".to_string());
         assert_eq!(
-            string.evaluate(&functions, &mut context).value,
+            string.evaluate(&functions, &mut context),
             Value::String("hello".to_string())
         );
         let numeric = Literal::Numeric(NumericValue::Integer(0));
         assert_eq!(
-            numeric.evaluate(&functions, &mut context).value,
+            numeric.evaluate(&functions, &mut context),
             Value::Numeric(NumericValue::Integer(0))
         );
     }
@@ -125,22 +170,29 @@ mod tests {
         let result = parse_literal("1");
         assert_eq!(result.is_ok(), true);
         assert_eq!(
-            result.unwrap().1.evaluate(&functions, &mut context).value,
+            result.unwrap().1.evaluate(&functions, &mut context),
             Value::Numeric(NumericValue::Integer(1))
         );
 
         let result = parse_literal(r#""hello""#);
         assert!(result.is_ok());
         assert_eq!(
-            result.unwrap().1.evaluate(&functions, &mut context).value,
+            result.unwrap().1.evaluate(&functions, &mut context),
             Value::String("hello".to_string()),
         );
 
         let result = parse_literal(r#""hello world""#);
         assert!(result.is_ok());
         assert_eq!(
-            result.unwrap().1.evaluate(&functions, &mut context).value,
+            result.unwrap().1.evaluate(&functions, &mut context),
             Value::String("hello world".to_string()),
         );
+
+        let result = parse_literal(r#""tab\there\n\"quoted\" \101\q""#);
+        assert!(result.is_ok());
+        assert_eq!(
+            result.unwrap().1.evaluate(&functions, &mut context),
+            Value::String("tab\there\n\"quoted\" A\\q".to_string()),
+        );
     }
 }
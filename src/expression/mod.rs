@@ -3,34 +3,93 @@ use std::fmt::Debug;
 
 use nom::{
     branch::alt,
-    character::complete::{multispace0, one_of},
-    combinator::map,
-    sequence::tuple,
+    character::complete::one_of,
+    multi::many0,
+    sequence::{preceded, tuple},
     IResult,
 };
 
-use crate::{context::MutableContext, function::Functions, printable::Printable, value::Value};
+use crate::{
+    context::{MutableContext, VariableStore},
+    function::Functions,
+    parse_utilities::{blank, newline_opt},
+    value::Value,
+};
 
+mod array;
+mod assignment;
 mod binary_comparison;
 mod binary_math;
 mod boolean;
+mod builtin;
+mod concatenation;
 mod field_reference;
 mod function;
+mod getline;
 mod increment;
 mod literal;
 mod regex_match;
+mod ternary;
+mod unary;
 pub(crate) mod variable;
 
+pub(crate) use array::{evaluate_subscripts, parse_subscripts};
 pub(crate) use variable::parse_variable_name;
 
 pub(crate) trait Expression: Debug {
-    fn evaluate(&self, functions: &Functions, context: &mut MutableContext) -> Printable<Value>;
+    fn evaluate(&self, functions: &Functions, context: &mut MutableContext) -> Value;
 
     fn regex<'a>(&'a self) -> Option<&'a Regex>;
+
+    /// A bare variable name, which can be passed to functions as either a scalar or an array
+    fn variable_name<'a>(&'a self) -> Option<&'a str> {
+        None
+    }
+
+    /// Expressions which can also be assigned to, e.g. the target of `sub` or `gsub`
+    fn assignable<'a>(&'a self) -> Option<&'a dyn Assign> {
+        None
+    }
 }
 
 pub(crate) trait Assign: Debug {
-    fn assign<'a>(&self, functions: &Functions, context: &mut MutableContext, value: Value);
+    /// Evaluates any subscripts or field index once, so that the same place can be both read
+    /// and written, e.g. by `a[i++] += 1`
+    fn resolve(&self, functions: &Functions, context: &mut MutableContext) -> Target;
+
+    fn assign(&self, functions: &Functions, context: &mut MutableContext, value: Value) {
+        self.resolve(functions, context).assign(context, value);
+    }
+}
+
+/// A place which can be assigned to, after its subscripts or field index have been evaluated
+#[derive(Debug)]
+pub(crate) enum Target {
+    Variable(String),
+    ArrayElement { array_name: String, key: String },
+    Field(i64),
+}
+
+impl Target {
+    pub(crate) fn fetch(&self, context: &mut MutableContext) -> Value {
+        match self {
+            Target::Variable(name) => context.fetch_variable(name),
+            Target::ArrayElement { array_name, key } => {
+                context.fetch_array_element(array_name, key)
+            }
+            Target::Field(index) => context.fetch_field(*index),
+        }
+    }
+
+    pub(crate) fn assign(&self, context: &mut MutableContext, value: Value) {
+        match self {
+            Target::Variable(name) => context.assign_variable(name, value),
+            Target::ArrayElement { array_name, key } => {
+                context.assign_array_element(array_name, key, value)
+            }
+            Target::Field(index) => context.assign_field(*index, &value),
+        }
+    }
 }
 
 pub(crate) trait AssignableExpression: Expression + Assign {}
@@ -44,44 +103,88 @@ type ExpressionParseResult<'a> = IResult<&'a str, Box<dyn Expression>>;
 /// until we reach literals and the parenthesized expressions.
 
 pub(crate) fn parse_assignable(input: &str) -> IResult<&str, Box<dyn AssignableExpression>> {
-    variable::parse_assignable_variable(input)
+    alt((
+        field_reference::parse_assignable_field_reference,
+        array::parse_assignable_array_element,
+        variable::parse_assignable_variable,
+    ))(input)
 }
 
 pub(crate) fn parse_expression(input: &str) -> ExpressionParseResult {
-    // Descending order of precedence
+    expression_parser(true)(input)
+}
+
+/// In the expression list of `print` or `printf`, a `>` outside of parentheses redirects the
+/// output instead of comparing
+pub(crate) fn parse_print_expression(input: &str) -> ExpressionParseResult {
+    expression_parser(false)(input)
+}
+
+fn expression_parser(allow_greater_than: bool) -> impl Fn(&str) -> ExpressionParseResult {
+    move |input: &str| {
+        // Descending order of precedence
+        let multiplication_parser = binary_math::multiplication_parser(parse_unary);
+        let addition_parser = binary_math::addition_parser(multiplication_parser);
+        let concatenation_parser = concatenation::concatenation_parser(addition_parser);
+        let getline_parser = getline::getline_pipe_parser(concatenation_parser);
+        let comparison_parser =
+            binary_comparison::comparison_parser(getline_parser, allow_greater_than);
+        let regex_parser = regex_match::regex_parser(comparison_parser);
+        let membership_parser = array::membership_parser(regex_parser);
+        let and_parser = boolean::and_parser(membership_parser);
+        let or_parser = boolean::or_parser(and_parser);
+        let ternary_parser =
+            ternary::ternary_parser(expression_parser(allow_greater_than), or_parser);
+        let assignment_parser =
+            assignment::assignment_parser(expression_parser(allow_greater_than), ternary_parser);
+
+        assignment_parser(input)
+    }
+}
+
+/// The tiers binding tighter than multiplication: `!` and signs, then exponents, increments,
+/// and field references
+fn parse_unary(input: &str) -> ExpressionParseResult {
     let field_reference_parser = field_reference::field_reference_parser(parse_primary);
     let increment_parser = increment::increment_decrement_parser(field_reference_parser);
-    let not_parser = boolean::not_parser(increment_parser);
-    let multiplication_parser = binary_math::multiplication_parser(not_parser);
-    let addition_parser = binary_math::addition_parser(multiplication_parser);
-    let comparison_parser = binary_comparison::comparison_parser(addition_parser);
-    let regex_parser = regex_match::regex_parser(comparison_parser);
-    let and_parser = boolean::and_parser(regex_parser);
-    let or_parser = boolean::or_parser(and_parser);
-
-    or_parser(input)
+    let exponent_parser = binary_math::exponent_parser(increment_parser, parse_unary);
+    let unary_parser = unary::unary_parser(exponent_parser);
+
+    unary_parser(input)
 }
 
 fn parse_primary(input: &str) -> ExpressionParseResult {
     alt((
+        getline::parse_simple_getline,
         function::parse_function_call,
         literal::parse_literal,
+        array::parse_array_element,
         variable::parse_variable,
         parse_parens,
     ))(input)
 }
 
+/// A parenthesized expression, or a parenthesized list of subscripts in `(i, j) in arr`, which is
+/// the only place such a list is allowed. Either way the first expression is only parsed once.
 fn parse_parens(input: &str) -> ExpressionParseResult {
-    map(
-        tuple((
-            one_of("("),
-            multispace0,
+    let (i, (_, _, first, mut rest, _, _)) = tuple((
+        one_of("("),
+        blank,
+        parse_expression,
+        many0(preceded(
+            tuple((blank, one_of(","), newline_opt)),
             parse_expression,
-            multispace0,
-            one_of(")"),
         )),
-        |(_, _, expression, _, _)| expression,
-    )(input)
+        blank,
+        one_of(")"),
+    ))(input)?;
+
+    if rest.is_empty() {
+        Result::Ok((i, first))
+    } else {
+        rest.insert(0, first);
+        array::parse_grouped_membership(rest, i)
+    }
 }
 
 #[cfg(test)]
@@ -98,18 +201,47 @@ mod tests {
         let result = parse_expression("( 1 )");
         assert_eq!(result.is_ok(), true);
         assert_eq!(
-            result.unwrap().1.evaluate(&functions, &mut context).value,
+            result.unwrap().1.evaluate(&functions, &mut context),
             Value::Numeric(NumericValue::Integer(1))
         );
 
         let result = parse_expression("(1) + (2.5)");
         assert_eq!(result.is_ok(), true);
         assert_eq!(
-            result.unwrap().1.evaluate(&functions, &mut context).value,
+            result.unwrap().1.evaluate(&functions, &mut context),
             Value::Numeric(NumericValue::Float(3.5))
         );
     }
 
+    #[test]
+    fn arithmetic_precedence() {
+        let (functions, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+        context.set_record_with_line("3 4");
+
+        for (text, expected) in vec![
+            ("-2 ^ 2", NumericValue::Integer(-4)),
+            ("2 ^ 3 ^ 2", NumericValue::Integer(512)),
+            ("2 ** 3 * 2", NumericValue::Integer(16)),
+            ("2 ^ -1", NumericValue::Float(0.5)),
+            ("-$1 ^ 2 + $2", NumericValue::Integer(-5)),
+            ("1 - -2 * 3", NumericValue::Integer(7)),
+            ("!2 + 1", NumericValue::Integer(1)),
+            ("10 % 4 ^ 2", NumericValue::Integer(10)),
+        ] {
+            let result = parse_expression(text);
+            assert!(result.is_ok());
+            let (remaining, expression) = result.unwrap();
+            assert_eq!(remaining, "", "{}", text);
+            assert_eq!(
+                expression.evaluate(&functions, &mut context),
+                Value::Numeric(expected),
+                "{}",
+                text
+            );
+        }
+    }
+
     #[test]
     fn test_boolean_precedence() {
         let (functions, mut variables) = empty_functions_and_variables();
@@ -118,22 +250,39 @@ mod tests {
         let result = parse_expression("1 && 1 || 0 && 1");
         assert_eq!(result.is_ok(), true);
         assert_eq!(
-            result.unwrap().1.evaluate(&functions, &mut context).value,
+            result.unwrap().1.evaluate(&functions, &mut context),
             Value::Numeric(NumericValue::Integer(1))
         );
 
         let result = parse_expression("1 && 0 || 0 && 1");
         assert_eq!(result.is_ok(), true);
         assert_eq!(
-            result.unwrap().1.evaluate(&functions, &mut context).value,
+            result.unwrap().1.evaluate(&functions, &mut context),
             Value::Numeric(NumericValue::Integer(0))
         );
 
         let result = parse_expression("0 || 1 && 0 || 1");
         assert_eq!(result.is_ok(), true);
         assert_eq!(
-            result.unwrap().1.evaluate(&functions, &mut context).value,
+            result.unwrap().1.evaluate(&functions, &mut context),
             Value::Numeric(NumericValue::Integer(1))
         );
     }
+
+    #[test]
+    fn deeply_nested_expressions_are_parsed_once() {
+        let (functions, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+
+        // Each level used to be parsed several times over, which made these take forever
+        let depth = 40;
+        for (open, close) in vec![("(", ")"), ("a[", "]"), ("$(", ")")] {
+            let text = format!("{}0{}", open.repeat(depth), close.repeat(depth));
+            let result = parse_expression(&text);
+            assert!(result.is_ok());
+            let (remaining, expression) = result.unwrap();
+            assert_eq!(remaining, "");
+            expression.evaluate(&functions, &mut context);
+        }
+    }
 }
@@ -4,15 +4,16 @@ use std::fmt::Debug;
 use nom::{
     branch::alt,
     bytes::complete::tag,
-    character::complete::multispace0,
-    sequence::{delimited, tuple},
+    combinator::{map, opt},
+    sequence::{delimited, pair},
 };
 
 use super::{Expression, ExpressionParseResult};
 use crate::{
     context::MutableContext,
     function::Functions,
-    printable::Printable,
+    parse_utilities::blank,
+    runtime_error::compile_regex,
     value::{NumericValue, Value},
 };
 
@@ -28,69 +29,46 @@ impl Expression for RegexMatch {
         None
     }
 
-    fn evaluate(&self, functions: &Functions, context: &mut MutableContext) -> Printable<Value> {
-        let Printable {
-            value: left_value,
-            output: mut left_output,
-        } = self.left.evaluate(functions, context);
-        let left_string = left_value.coerce_to_string();
+    fn evaluate(&self, functions: &Functions, context: &mut MutableContext) -> Value {
+        let left_value = self.left.evaluate(functions, context);
+        let left_string = context.coerce_to_string(&left_value);
 
         let matches = match self.right.regex() {
             Some(r) => r.is_match(&left_string),
             None => {
-                let Printable {
-                    value: right_value,
-                    output: mut right_output,
-                } = self.right.evaluate(functions, context);
-                let right_string = right_value.coerce_to_string();
-                left_output.append(&mut right_output);
-                Regex::new(&right_string).unwrap().is_match(&left_string)
+                let right_value = self.right.evaluate(functions, context);
+                let right_string = context.coerce_to_string(&right_value);
+                compile_regex(&right_string).is_match(&left_string)
             }
         };
         let int_value = if matches ^ self.negated { 1 } else { 0 };
-
-        Printable {
-            value: Value::Numeric(NumericValue::Integer(int_value)),
-            output: left_output,
-        }
+        Value::Numeric(NumericValue::Integer(int_value))
     }
 }
 
+/// The left side is parsed only once, whether or not a match operator follows it
 pub(super) fn regex_parser<F>(next_parser: F) -> impl Fn(&str) -> ExpressionParseResult
 where
     F: Fn(&str) -> ExpressionParseResult,
 {
     move |input: &str| {
-        alt((definite_regex_parser(|i| next_parser(i)), |i| {
-            next_parser(i)
-        }))(input)
-    }
-}
-
-fn definite_regex_parser<F>(next_parser: F) -> impl Fn(&str) -> ExpressionParseResult
-where
-    F: Fn(&str) -> ExpressionParseResult,
-{
-    move |input: &str| {
-        let (i, (left, operator, right)) = tuple((
-            |i| next_parser(i),
-            delimited(multispace0, alt((tag("~"), tag("!~"))), multispace0),
-            |i| next_parser(i),
-        ))(input)?;
+        let parse_operator = delimited(
+            blank,
+            alt((map(tag("~"), |_| false), map(tag("!~"), |_| true))),
+            blank,
+        );
+        let (i, (left, regex_match)) =
+            pair(&next_parser, opt(pair(parse_operator, &next_parser)))(input)?;
 
-        let negated = match operator {
-            "~" => false,
-            "!~" => true,
-            _ => panic!("Unexpected regex operator length: {}", operator),
-        };
-        Result::Ok((
-            i,
-            Box::new(RegexMatch {
+        let expression = match regex_match {
+            Some((negated, right)) => Box::new(RegexMatch {
                 left: left,
                 right: right,
-                negated,
+                negated: negated,
             }),
-        ))
+            None => left,
+        };
+        Result::Ok((i, expression))
     }
 }
 
@@ -98,7 +76,7 @@ where
 mod tests {
     use super::super::{binary_math::addition_parser, literal::parse_literal};
     use super::*;
-    use crate::test_utilities::empty_functions_and_variables;
+    use crate::test_utilities::{empty_functions_and_variables, evaluate};
 
     #[test]
     fn test_regex_match() {
@@ -110,7 +88,7 @@ mod tests {
         assert!(result.is_ok());
         let expression = result.unwrap().1;
         assert_eq!(
-            expression.evaluate(&functions, &mut context).value,
+            expression.evaluate(&functions, &mut context),
             Value::Numeric(NumericValue::Integer(0)),
         );
 
@@ -124,7 +102,7 @@ mod tests {
         let (remainder, expression) = result.unwrap();
         assert_eq!(remainder, "");
         assert_eq!(
-            expression.evaluate(&functions, &mut context).value,
+            expression.evaluate(&functions, &mut context),
             Value::Numeric(NumericValue::Integer(1)),
         );
 
@@ -132,8 +110,16 @@ mod tests {
         assert!(result.is_ok());
         let expression = result.unwrap().1;
         assert_eq!(
-            expression.evaluate(&functions, &mut context).value,
+            expression.evaluate(&functions, &mut context),
             Value::Numeric(NumericValue::Integer(1)),
         );
     }
+
+    #[test]
+    #[should_panic(expected = "regular expression compile failed")]
+    fn regexes_which_do_not_compile_are_an_error() {
+        let (_, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+        evaluate(r#""a" ~ "(""#, &mut context);
+    }
 }
@@ -0,0 +1,117 @@
+use regex::Regex;
+
+use nom::{
+    character::complete::one_of,
+    combinator::opt,
+    sequence::{preceded, tuple},
+};
+
+use super::{Expression, ExpressionParseResult};
+use crate::{
+    context::MutableContext,
+    function::Functions,
+    parse_utilities::{blank, newline_opt},
+    value::Value,
+};
+
+/// `condition ? if_true : if_false`, which only evaluates the branch that was chosen
+#[derive(Debug)]
+struct Ternary {
+    condition: Box<dyn Expression>,
+    if_true: Box<dyn Expression>,
+    if_false: Box<dyn Expression>,
+}
+
+impl Expression for Ternary {
+    fn regex<'a>(&'a self) -> Option<&'a Regex> {
+        None
+    }
+
+    fn evaluate(&self, functions: &Functions, context: &mut MutableContext) -> Value {
+        if self
+            .condition
+            .evaluate(functions, context)
+            .coercion_to_boolean()
+        {
+            self.if_true.evaluate(functions, context)
+        } else {
+            self.if_false.evaluate(functions, context)
+        }
+    }
+}
+
+/// The conditional groups to the right, so `a ? b : c ? d : e` is `a ? b : (c ? d : e)`
+pub(super) fn ternary_parser<F, G>(
+    parse_branch: F,
+    next_parser: G,
+) -> impl Fn(&str) -> ExpressionParseResult
+where
+    F: Fn(&str) -> ExpressionParseResult,
+    G: Fn(&str) -> ExpressionParseResult,
+{
+    move |input: &str| {
+        let parse_branches = tuple((
+            preceded(tuple((blank, one_of("?"), newline_opt, blank)), |i| {
+                parse_branch(i)
+            }),
+            preceded(tuple((blank, one_of(":"), newline_opt, blank)), |i| {
+                parse_branch(i)
+            }),
+        ));
+
+        let (i, (condition, branches)) = tuple((|i| next_parser(i), opt(parse_branches)))(input)?;
+        let expression = match branches {
+            Some((if_true, if_false)) => Box::new(Ternary {
+                condition: condition,
+                if_true: if_true,
+                if_false: if_false,
+            }),
+            None => condition,
+        };
+        Result::Ok((i, expression))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parse_expression;
+    use super::*;
+    use crate::context::VariableStore;
+    use crate::test_utilities::empty_functions_and_variables;
+    use crate::value::NumericValue;
+
+    #[test]
+    fn only_the_chosen_branch_is_evaluated() {
+        let (functions, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+
+        for (text, expected) in vec![
+            (r#"1 ? "yes" : "no""#, Value::String("yes".to_string())),
+            (r#"0 ? "yes" : "no""#, Value::String("no".to_string())),
+            (r#"0 ? "a" : 1 ? "b" : "c""#, Value::String("b".to_string())),
+            (r#"1 ? 0 ? "a" : "b" : "c""#, Value::String("b".to_string())),
+            ("0 || 1 ? 2 : 3", Value::Numeric(NumericValue::Integer(2))),
+            (
+                "x = 1 ? ++y : ++z",
+                Value::Numeric(NumericValue::Integer(1)),
+            ),
+        ] {
+            let result = parse_expression(text);
+            assert!(result.is_ok());
+            let (remaining, expression) = result.unwrap();
+            assert_eq!(remaining, "", "{}", text);
+            assert_eq!(
+                expression.evaluate(&functions, &mut context),
+                expected,
+                "{}",
+                text
+            );
+        }
+
+        assert_eq!(
+            context.fetch_variable("y"),
+            Value::Numeric(NumericValue::Integer(1))
+        );
+        assert_eq!(context.fetch_variable("z"), Value::Uninitialized);
+    }
+}
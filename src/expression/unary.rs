@@ -0,0 +1,148 @@
+use regex::Regex;
+
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::one_of,
+    combinator::{map, not, peek},
+    multi::many0,
+    sequence::{pair, terminated},
+};
+
+use super::{Expression, ExpressionParseResult};
+use crate::{
+    context::MutableContext,
+    function::Functions,
+    parse_utilities::blank,
+    value::{NumericValue, Value},
+};
+
+#[derive(Debug)]
+enum Operator {
+    Not,
+    Minus,
+    Plus,
+}
+
+#[derive(Debug)]
+struct Unary {
+    operator: Operator,
+    expression: Box<dyn Expression>,
+}
+
+impl Expression for Unary {
+    fn regex<'a>(&'a self) -> Option<&'a Regex> {
+        None
+    }
+
+    fn evaluate(&self, functions: &Functions, context: &mut MutableContext) -> Value {
+        let value = self.expression.evaluate(functions, context);
+        match (&self.operator, value.coerce_to_numeric()) {
+            (Operator::Not, _) => {
+                let int_value = if value.coercion_to_boolean() { 0 } else { 1 };
+                Value::Numeric(NumericValue::Integer(int_value))
+            }
+            (Operator::Minus, NumericValue::Integer(i)) => {
+                Value::Numeric(NumericValue::Integer(-i))
+            }
+            (Operator::Minus, NumericValue::Float(f)) => Value::Numeric(NumericValue::Float(-f)),
+            (Operator::Plus, number) => Value::Numeric(number),
+        }
+    }
+}
+
+/// `!`, `-`, and `+` in front of an expression, e.g. `!x` or `- -x`
+///
+/// A doubled `--` or `++` is left alone, since it is a decrement or increment instead.
+pub(super) fn unary_parser<F>(next_parser: F) -> impl Fn(&str) -> ExpressionParseResult
+where
+    F: Fn(&str) -> ExpressionParseResult,
+{
+    move |input: &str| {
+        let parse_operator = terminated(
+            alt((
+                map(one_of("!"), |_| Operator::Not),
+                map(terminated(one_of("-"), not(peek(tag("-")))), |_| {
+                    Operator::Minus
+                }),
+                map(terminated(one_of("+"), not(peek(tag("+")))), |_| {
+                    Operator::Plus
+                }),
+            )),
+            blank,
+        );
+
+        let (i, (operators, inner_expression)) = pair(many0(parse_operator), &next_parser)(input)?;
+
+        // The operator closest to the expression applies first
+        let expression = operators
+            .into_iter()
+            .rev()
+            .fold(inner_expression, |inner, operator| {
+                Box::new(Unary {
+                    operator: operator,
+                    expression: inner,
+                })
+            });
+        Result::Ok((i, expression))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::literal::*;
+    use super::super::parse_expression;
+    use super::*;
+    use crate::test_utilities::empty_functions_and_variables;
+
+    #[test]
+    fn test_not_parsing() {
+        let (functions, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+        let parser = unary_parser(parse_literal);
+
+        for (text, expected) in vec![
+            (r#"!1"#, 0),
+            (r#"!0"#, 1),
+            (r#"!"a""#, 0),
+            (r#"!"""#, 1),
+            (r#"!!!!!0"#, 1),
+            (r#"!!"abc""#, 1),
+        ] {
+            let result = parser(text);
+            assert!(result.is_ok());
+            assert_eq!(
+                result.unwrap().1.evaluate(&functions, &mut context),
+                Value::Numeric(NumericValue::Integer(expected)),
+            );
+        }
+
+        let result = parser(r#""abc""#);
+        assert!(result.is_ok());
+        assert_eq!(
+            result.unwrap().1.evaluate(&functions, &mut context),
+            Value::String("abc".to_string()),
+        );
+    }
+
+    #[test]
+    fn sign_operators_make_numbers() {
+        let (functions, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+
+        for (text, expected) in vec![
+            ("-2", Value::Numeric(NumericValue::Integer(-2))),
+            ("- -2.5", Value::Numeric(NumericValue::Float(2.5))),
+            (r#"+"3x""#, Value::Numeric(NumericValue::Integer(3))),
+            ("-!0", Value::Numeric(NumericValue::Integer(-1))),
+            ("!-1", Value::Numeric(NumericValue::Integer(0))),
+            ("1 - -1", Value::Numeric(NumericValue::Integer(2))),
+        ] {
+            let result = parse_expression(text);
+            assert!(result.is_ok());
+            let (remaining, expression) = result.unwrap();
+            assert_eq!(remaining, "");
+            assert_eq!(expression.evaluate(&functions, &mut context), expected);
+        }
+    }
+}
@@ -1,12 +1,11 @@
 use regex::Regex;
 
-use nom::{re_find, IResult};
+use nom::{combinator::verify, re_find, IResult};
 
-use super::{Assign, Expression, AssignableExpression, ExpressionParseResult};
+use super::{Assign, AssignableExpression, Expression, ExpressionParseResult, Target};
 use crate::{
     context::{MutableContext, VariableStore},
     function::Functions,
-    printable::Printable,
     value::Value,
 };
 
@@ -20,17 +19,25 @@ impl Expression for Variable {
         None
     }
 
-    fn evaluate(&self, _functions: &Functions, context: &mut MutableContext) -> Printable<Value> {
-        Printable::wrap(context.fetch_variable(&self.variable_name))
+    fn evaluate(&self, _functions: &Functions, context: &mut MutableContext) -> Value {
+        context.fetch_variable(&self.variable_name)
+    }
+
+    fn variable_name<'a>(&'a self) -> Option<&'a str> {
+        Some(&self.variable_name)
+    }
+
+    fn assignable<'a>(&'a self) -> Option<&'a dyn Assign> {
+        Some(self)
     }
 }
 
 impl Assign for Variable {
-    fn assign<'a>(&self, functions: &Functions, context: &mut MutableContext, value: Value) {
+    fn resolve(&self, functions: &Functions, _context: &mut MutableContext) -> Target {
         if let Some(_) = functions.get(&self.variable_name) {
             panic!("can't assign to {}; it's a function", self.variable_name);
         }
-        context.assign_variable(&self.variable_name, value);
+        Target::Variable(self.variable_name.clone())
     }
 }
 
@@ -47,7 +54,9 @@ pub(super) fn parse_variable(input: &str) -> ExpressionParseResult {
     ))
 }
 
-pub(super) fn parse_assignable_variable(input: &str) -> IResult<&str, Box<dyn AssignableExpression>> {
+pub(super) fn parse_assignable_variable(
+    input: &str,
+) -> IResult<&str, Box<dyn AssignableExpression>> {
     let (i, name) = parse_variable_name(input)?;
 
     Result::Ok((
@@ -58,9 +67,18 @@ pub(super) fn parse_assignable_variable(input: &str) -> IResult<&str, Box<dyn As
     ))
 }
 
+/// Names which can never be used for variables or functions
+const KEYWORDS: [&str; 19] = [
+    "BEGIN", "END", "break", "continue", "delete", "do", "else", "exit", "for", "function",
+    "getline", "if", "in", "next", "nextfile", "print", "printf", "return", "while",
+];
+
 // Public for use in parse_args
-pub fn parse_variable_name(input: &str) -> IResult<&str, &str> {
-    re_find!(input, r"^[A-Za-z_][A-Za-z0-9_]*")
+pub fn parse_variable_name<'a>(input: &'a str) -> IResult<&'a str, &'a str> {
+    verify(
+        |i: &'a str| re_find!(i, r"^[A-Za-z_][A-Za-z0-9_]*"),
+        |name: &&str| !KEYWORDS.contains(name),
+    )(input)
 }
 
 #[cfg(test)]
@@ -81,8 +99,7 @@ mod tests {
             Variable {
                 variable_name: "foo".to_string()
             }
-            .evaluate(&functions, &mut context)
-            .value,
+            .evaluate(&functions, &mut context),
             value,
         );
     }
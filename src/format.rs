@@ -0,0 +1,456 @@
+use std::iter::Peekable;
+use std::str::Chars;
+
+use crate::value::{NumericValue, Value, DEFAULT_NUMBER_FORMAT};
+
+//
+// Implementation of the C-style format strings used by `printf` and `sprintf`
+//
+
+#[derive(Default)]
+struct Specification {
+    left_align: bool,
+    plus_sign: bool,
+    space_sign: bool,
+    alternate_form: bool,
+    zero_pad: bool,
+    width: usize,
+    precision: Option<usize>,
+}
+
+pub(crate) fn sprintf(format: &str, arguments: &[Value]) -> String {
+    sprintf_with_conversion_format(format, arguments, DEFAULT_NUMBER_FORMAT)
+}
+
+/// Numbers formatted with `%s` are converted to strings using `conversion_format` (i.e. CONVFMT)
+pub(crate) fn sprintf_with_conversion_format(
+    format: &str,
+    arguments: &[Value],
+    conversion_format: &str,
+) -> String {
+    let mut output = String::new();
+    let mut arguments = arguments.iter();
+    let mut chars = format.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            output.push(c);
+            continue;
+        }
+
+        // Keep the original text around so malformed specifications can be printed as-is
+        let mut raw = String::from("%");
+        let mut spec = Specification::default();
+
+        while let Some(flag) = chars.peek().copied() {
+            match flag {
+                '-' => spec.left_align = true,
+                '+' => spec.plus_sign = true,
+                ' ' => spec.space_sign = true,
+                '#' => spec.alternate_form = true,
+                '0' => spec.zero_pad = true,
+                _ => break,
+            }
+            raw.push(flag);
+            chars.next();
+        }
+
+        if chars.peek() == Some(&'*') {
+            raw.push('*');
+            chars.next();
+            let width = next_integer(&mut arguments);
+            // A negative width taken from the arguments means left-alignment
+            if width < 0 {
+                spec.left_align = true;
+            }
+            spec.width = width.unsigned_abs() as usize;
+        } else {
+            spec.width = parse_digits(&mut chars, &mut raw).unwrap_or(0);
+        }
+
+        if chars.peek() == Some(&'.') {
+            raw.push('.');
+            chars.next();
+            if chars.peek() == Some(&'*') {
+                raw.push('*');
+                chars.next();
+                let precision = next_integer(&mut arguments);
+                // A negative precision taken from the arguments is treated as if it were omitted
+                spec.precision = if precision < 0 {
+                    None
+                } else {
+                    Some(precision as usize)
+                };
+            } else {
+                spec.precision = Some(parse_digits(&mut chars, &mut raw).unwrap_or(0));
+            }
+        }
+
+        match chars.next() {
+            Some('%') => output.push('%'),
+            Some(conversion) if "diouxXcseEfFgG".contains(conversion) => {
+                let argument = arguments.next().unwrap_or(&Value::Uninitialized);
+                output.push_str(&format_argument(
+                    conversion,
+                    argument,
+                    &spec,
+                    conversion_format,
+                ));
+            }
+            Some(other) => {
+                raw.push(other);
+                output.push_str(&raw);
+            }
+            None => output.push_str(&raw),
+        }
+    }
+
+    output
+}
+
+fn parse_digits(chars: &mut Peekable<Chars>, raw: &mut String) -> Option<usize> {
+    let mut digits = String::new();
+    while let Some(c) = chars.peek().copied() {
+        if !c.is_ascii_digit() {
+            break;
+        }
+        digits.push(c);
+        chars.next();
+    }
+    raw.push_str(&digits);
+    digits.parse::<usize>().ok()
+}
+
+fn next_integer<'a, I>(arguments: &mut I) -> i64
+where
+    I: Iterator<Item = &'a Value>,
+{
+    arguments.next().map(to_integer).unwrap_or(0)
+}
+
+fn to_integer(value: &Value) -> i64 {
+    match value.coerce_to_numeric() {
+        NumericValue::Integer(i) => i,
+        NumericValue::Float(f) => f.trunc() as i64,
+    }
+}
+
+fn format_argument(
+    conversion: char,
+    argument: &Value,
+    spec: &Specification,
+    conversion_format: &str,
+) -> String {
+    match conversion {
+        'd' | 'i' => {
+            let number = to_integer(argument);
+            let sign = if number < 0 { "-" } else { positive_sign(spec) };
+            let digits = apply_integer_precision(number.unsigned_abs().to_string(), spec);
+            pad(sign, &digits, spec, spec.precision.is_none())
+        }
+        'o' | 'u' | 'x' | 'X' => {
+            // Negative numbers wrap around, as they would when cast to unsigned in C
+            let number = to_integer(argument) as u64;
+            let digits = match conversion {
+                'o' => format!("{:o}", number),
+                'x' => format!("{:x}", number),
+                'X' => format!("{:X}", number),
+                _ => number.to_string(),
+            };
+            let mut digits = apply_integer_precision(digits, spec);
+            let prefix = match conversion {
+                'o' if spec.alternate_form && !digits.starts_with('0') => {
+                    digits.insert(0, '0');
+                    ""
+                }
+                'x' if spec.alternate_form && number != 0 => "0x",
+                'X' if spec.alternate_form && number != 0 => "0X",
+                _ => "",
+            };
+            pad(prefix, &digits, spec, spec.precision.is_none())
+        }
+        'c' => {
+            let character = match argument {
+                Value::Numeric(_) => std::char::from_u32(to_integer(argument) as u32)
+                    .map(|c| c.to_string())
+                    .unwrap_or_default(),
+                _ => argument
+                    .coerce_to_string_with_format(conversion_format)
+                    .chars()
+                    .next()
+                    .map(|c| c.to_string())
+                    .unwrap_or_default(),
+            };
+            pad("", &character, spec, false)
+        }
+        's' => {
+            let string = argument.coerce_to_string_with_format(conversion_format);
+            let string = match spec.precision {
+                Some(precision) => string.chars().take(precision).collect(),
+                None => string,
+            };
+            pad("", &string, spec, false)
+        }
+        _ => {
+            let number = argument.coerce_to_float();
+            // Like C, the sign bit is honored even for NaN
+            let sign = if number.is_sign_negative() {
+                "-"
+            } else {
+                positive_sign(spec)
+            };
+            if !number.is_finite() {
+                let body = if number.is_nan() { "nan" } else { "inf" };
+                let body = if conversion.is_ascii_uppercase() {
+                    body.to_uppercase()
+                } else {
+                    body.to_string()
+                };
+                return pad(sign, &body, spec, false);
+            }
+            let body = format_float(conversion, number.abs(), spec);
+            pad(sign, &body, spec, true)
+        }
+    }
+}
+
+fn positive_sign(spec: &Specification) -> &'static str {
+    if spec.plus_sign {
+        "+"
+    } else if spec.space_sign {
+        " "
+    } else {
+        ""
+    }
+}
+
+/// For integers, the precision is the minimum number of digits to print
+fn apply_integer_precision(digits: String, spec: &Specification) -> String {
+    match spec.precision {
+        Some(0) if digits == "0" => String::new(),
+        Some(precision) if precision > digits.len() => {
+            format!("{}{}", "0".repeat(precision - digits.len()), digits)
+        }
+        _ => digits,
+    }
+}
+
+fn pad(prefix: &str, body: &str, spec: &Specification, allow_zero_padding: bool) -> String {
+    let length = prefix.chars().count() + body.chars().count();
+    if spec.width <= length {
+        return format!("{}{}", prefix, body);
+    }
+
+    let padding = spec.width - length;
+    if spec.left_align {
+        format!("{}{}{}", prefix, body, " ".repeat(padding))
+    } else if spec.zero_pad && allow_zero_padding {
+        format!("{}{}{}", prefix, "0".repeat(padding), body)
+    } else {
+        format!("{}{}{}", " ".repeat(padding), prefix, body)
+    }
+}
+
+fn format_float(conversion: char, number: f64, spec: &Specification) -> String {
+    let precision = spec.precision.unwrap_or(6);
+    let uppercase = conversion.is_ascii_uppercase();
+    match conversion {
+        'f' | 'F' => format_fixed(number, precision, spec.alternate_form),
+        'e' | 'E' => format_exponential(number, precision, spec.alternate_form, uppercase),
+        _ => {
+            // %g picks whichever of %f and %e is more compact for the number's magnitude
+            let significant_digits = if precision == 0 { 1 } else { precision };
+            let exponent = exponent_of(number, significant_digits - 1);
+            let formatted = if exponent < -4 || exponent >= significant_digits as i32 {
+                format_exponential(
+                    number,
+                    significant_digits - 1,
+                    spec.alternate_form,
+                    uppercase,
+                )
+            } else {
+                format_fixed(
+                    number,
+                    (significant_digits as i32 - 1 - exponent) as usize,
+                    spec.alternate_form,
+                )
+            };
+            if spec.alternate_form {
+                formatted
+            } else {
+                strip_trailing_zeros(&formatted)
+            }
+        }
+    }
+}
+
+fn format_fixed(number: f64, precision: usize, alternate_form: bool) -> String {
+    let mut formatted = format!("{:.*}", precision, number);
+    if alternate_form && precision == 0 {
+        formatted.push('.');
+    }
+    formatted
+}
+
+/// The decimal exponent of the number once it has been rounded to the given precision
+fn exponent_of(number: f64, precision: usize) -> i32 {
+    let formatted = format!("{:.*e}", precision, number);
+    let (_, exponent) = formatted.split_at(formatted.find('e').unwrap() + 1);
+    exponent.parse::<i32>().unwrap()
+}
+
+fn format_exponential(
+    number: f64,
+    precision: usize,
+    alternate_form: bool,
+    uppercase: bool,
+) -> String {
+    let formatted = format!("{:.*e}", precision, number);
+    let (mantissa, exponent) = formatted.split_at(formatted.find('e').unwrap());
+    let exponent = exponent[1..].parse::<i32>().unwrap();
+
+    let mut result = mantissa.to_string();
+    if alternate_form && precision == 0 {
+        result.push('.');
+    }
+    result.push(if uppercase { 'E' } else { 'e' });
+    result.push(if exponent < 0 { '-' } else { '+' });
+    // C always prints at least two digits of exponent
+    result.push_str(&format!("{:02}", exponent.abs()));
+    result
+}
+
+fn strip_trailing_zeros(formatted: &str) -> String {
+    let (number, exponent) = match formatted.find(['e', 'E']) {
+        Some(index) => formatted.split_at(index),
+        None => (formatted, ""),
+    };
+    if !number.contains('.') {
+        return formatted.to_string();
+    }
+    let number = number.trim_end_matches('0').trim_end_matches('.');
+    format!("{}{}", number, exponent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn string(s: &str) -> Value {
+        Value::String(s.to_string())
+    }
+
+    fn integer(i: i64) -> Value {
+        Value::Numeric(NumericValue::Integer(i))
+    }
+
+    fn float(f: f64) -> Value {
+        Value::Numeric(NumericValue::Float(f))
+    }
+
+    #[test]
+    fn test_integer_conversions() {
+        assert_eq!(sprintf("%d", &[integer(42)]), "42");
+        assert_eq!(sprintf("%i", &[float(-3.9)]), "-3");
+        assert_eq!(
+            sprintf("%5d|%-5d|%05d", &[integer(42), integer(42), integer(-42)]),
+            "   42|42   |-0042"
+        );
+        assert_eq!(sprintf("%+d % d", &[integer(1), integer(1)]), "+1  1");
+        assert_eq!(
+            sprintf("%.3d|%6.3d|%.0d", &[integer(7), integer(7), integer(0)]),
+            "007|   007|"
+        );
+        assert_eq!(sprintf("%d", &[string("12abc")]), "12");
+        assert_eq!(
+            sprintf(
+                "%o %#o %x %#X %u",
+                &[
+                    integer(8),
+                    integer(8),
+                    integer(255),
+                    integer(255),
+                    integer(3)
+                ]
+            ),
+            "10 010 ff 0XFF 3"
+        );
+        assert_eq!(sprintf("%x", &[integer(-1)]), "ffffffffffffffff");
+    }
+
+    #[test]
+    fn test_string_conversions() {
+        assert_eq!(sprintf("%s!", &[string("hello")]), "hello!");
+        assert_eq!(
+            sprintf(
+                "[%8s][%-8s][%.2s]",
+                &[string("abc"), string("abc"), string("abc")]
+            ),
+            "[     abc][abc     ][ab]"
+        );
+        assert_eq!(sprintf("%s", &[integer(12)]), "12");
+        assert_eq!(
+            sprintf("%c%c%c", &[integer(65), string("bcd"), integer(0x263A)]),
+            "Ab\u{263A}"
+        );
+        assert_eq!(sprintf("%3c", &[string("x")]), "  x");
+        assert_eq!(
+            sprintf_with_conversion_format("%s %d", &[float(3.14159), float(3.14159)], "%.2g"),
+            "3.1 3"
+        );
+    }
+
+    #[test]
+    fn test_float_conversions() {
+        assert_eq!(sprintf("%f", &[float(3.14159)]), "3.141590");
+        assert_eq!(
+            sprintf(
+                "%.2f|%8.3F|%-8.1f|%08.2f",
+                &[float(2.345), float(-1.5), float(1.25), float(-3.14159)]
+            ),
+            "2.35|  -1.500|1.2     |-0003.14"
+        );
+        assert_eq!(sprintf("%.0f %#.0f", &[float(2.5), float(3.0)]), "2 3.");
+        assert_eq!(sprintf("%e", &[float(1234.5678)]), "1.234568e+03");
+        assert_eq!(sprintf("%.2E", &[float(0.000123)]), "1.23E-04");
+        assert_eq!(
+            sprintf(
+                "%g %g %g",
+                &[float(100000.0), float(1000000.0), float(0.0001)]
+            ),
+            "100000 1e+06 0.0001"
+        );
+        assert_eq!(
+            sprintf(
+                "%g %G %.3g",
+                &[float(0.00001234), float(1.5e-10), float(3.14159)]
+            ),
+            "1.234e-05 1.5E-10 3.14"
+        );
+        assert_eq!(sprintf("%#g %g", &[float(1.5), integer(0)]), "1.50000 0");
+        assert_eq!(sprintf("%.6g", &[float(1.0 / 3.0)]), "0.333333");
+        assert_eq!(
+            sprintf("%f %F", &[float(f64::INFINITY), float(f64::NEG_INFINITY)]),
+            "inf -INF"
+        );
+    }
+
+    #[test]
+    fn test_star_width_and_precision() {
+        assert_eq!(sprintf("[%*d]", &[integer(5), integer(42)]), "[   42]");
+        assert_eq!(sprintf("[%*d]", &[integer(-5), integer(42)]), "[42   ]");
+        assert_eq!(sprintf("[%.*f]", &[integer(2), float(3.14159)]), "[3.14]");
+        assert_eq!(
+            sprintf("[%*.*s]", &[integer(6), integer(3), string("abcdef")]),
+            "[   abc]"
+        );
+    }
+
+    #[test]
+    fn test_escapes_and_malformed_specifications() {
+        assert_eq!(sprintf("100%%", &[]), "100%");
+        assert_eq!(sprintf("%d%%", &[integer(50)]), "50%");
+        assert_eq!(sprintf("%z %", &[]), "%z %");
+        assert_eq!(sprintf("%s|%d", &[]), "|0");
+    }
+}
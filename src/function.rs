@@ -1,7 +1,7 @@
 use nom::{
     bytes::complete::tag,
-    character::complete::{multispace0, multispace1, one_of},
-    combinator::map,
+    character::complete::one_of,
+    combinator::{map, opt},
     multi::many0,
     sequence::{pair, preceded, tuple},
     IResult,
@@ -10,10 +10,14 @@ use std::collections::HashMap;
 use std::ops::Index;
 
 use crate::{
-    action::{parse_action, Action},
-    context::{stack_frame::StackFrame, MutableContext},
+    action::{parse_action, Action, JumpScope},
+    context::{
+        stack_frame::{Array, StackFrame, Variable},
+        MutableContext,
+    },
+    control_flow::ControlFlow,
     expression::variable::parse_variable_name,
-    printable::Printable,
+    parse_utilities::{blank, keyword, newline_opt},
     value::{Value, UNINITIALIZED_VALUE},
 };
 
@@ -25,14 +29,29 @@ pub(crate) struct FunctionDefinition {
 
 pub(crate) type Functions = HashMap<String, FunctionDefinition>;
 
+/// Scalars are passed into functions by value, but arrays are passed by reference
+pub(crate) enum Argument {
+    Scalar(Value),
+    Array(Array),
+    // An unset variable, which the callee may turn into an array on the caller's behalf
+    Untyped(String),
+}
+
 impl FunctionDefinition {
+    pub(crate) fn check_jumps(&self) -> Result<(), &'static str> {
+        self.body.check_jumps(JumpScope {
+            in_loop: false,
+            in_function: true,
+        })
+    }
+
     pub(crate) fn invoke_with(
         &self,
-        values: Vec<Value>,
+        arguments: Vec<Argument>,
         functions: &Functions,
         context: &mut MutableContext,
-    ) -> Printable<Value> {
-        let (num, expected_num) = (values.len(), self.variable_names.len());
+    ) -> Value {
+        let (num, expected_num) = (arguments.len(), self.variable_names.len());
         if num > expected_num {
             panic!(
                 "function {} called with {} args, uses only {}",
@@ -41,22 +60,40 @@ impl FunctionDefinition {
         }
 
         let mut frame = StackFrame::empty();
-        for i in 0..values.len() {
-            let (name, value) = (self.variable_names.index(i), values[i].clone());
-            frame.assign_variable(name, value);
+        for (name, argument) in self.variable_names.iter().zip(arguments.iter()) {
+            match argument {
+                Argument::Scalar(value) => frame.assign_variable(name, value.clone()),
+                Argument::Array(array) => frame.bind_array(name, array.clone()),
+                Argument::Untyped(_) => frame.assign_variable(name, UNINITIALIZED_VALUE.clone()),
+            }
         }
-        for i in values.len()..self.variable_names.len() {
+        for i in arguments.len()..self.variable_names.len() {
             frame.assign_variable(self.variable_names.index(i), UNINITIALIZED_VALUE.clone());
         }
 
-        // TODO: Make Functions expressions too
-        // Right now, a function can only be invoked as a StatementEnum with printable outputs.
-        // In the future, a function will need to be both a "statement" (returning outputs) AND an
-        // expression (having a nestable value)
-        context
-            .with_stack_frame(frame, |c| self.body.output_for_line(functions, c))
-            // TODO: Actually return a proper return value
-            .and_then(|_| Printable::wrap(UNINITIALIZED_VALUE.clone()))
+        let (flow, frame) =
+            context.with_stack_frame(frame, |c| self.body.output_for_line(functions, c));
+
+        // Any untyped argument the callee used as an array becomes that array in the caller
+        for (name, argument) in self.variable_names.iter().zip(arguments.iter()) {
+            if let (Argument::Untyped(caller_name), Some(Variable::Array(array))) =
+                (argument, frame.fetch_binding(name))
+            {
+                context.bind_array(caller_name, array);
+            }
+        }
+
+        match flow {
+            ControlFlow::Return(value) => value,
+            ControlFlow::Normal => UNINITIALIZED_VALUE.clone(),
+            ControlFlow::Break | ControlFlow::Continue => {
+                unreachable!("break and continue outside of a loop are rejected when parsing")
+            }
+            _ => {
+                context.interrupt_with(flow);
+                UNINITIALIZED_VALUE.clone()
+            }
+        }
     }
 }
 
@@ -65,7 +102,7 @@ pub(crate) fn parse_function(input: &str) -> IResult<&str, FunctionDefinition> {
         pair(
             parse_variable_name,
             many0(preceded(
-                tuple((multispace0, one_of(","), multispace0)),
+                tuple((blank, one_of(","), newline_opt)),
                 parse_variable_name,
             )),
         ),
@@ -76,21 +113,25 @@ pub(crate) fn parse_function(input: &str) -> IResult<&str, FunctionDefinition> {
     );
     map(
         tuple((
-            tag("function"),
-            multispace1,
+            keyword("function"),
+            blank,
             parse_variable_name,
-            multispace0,
+            blank,
             tag("("),
-            multispace0,
-            parse_variable_list,
-            multispace0,
+            blank,
+            opt(parse_variable_list),
+            blank,
             tag(")"),
-            multispace0,
+            newline_opt,
             parse_action,
         )),
         |(_, _, func_name, _, _, _, variables, _, _, _, body)| FunctionDefinition {
             name: func_name.to_string(),
-            variable_names: variables.iter().map(|s| s.to_string()).collect(),
+            variable_names: variables
+                .unwrap_or_default()
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
             body: body,
         },
     )(input)
@@ -99,6 +140,10 @@ pub(crate) fn parse_function(input: &str) -> IResult<&str, FunctionDefinition> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{
+        context::VariableStore, expression::parse_expression,
+        test_utilities::empty_functions_and_variables, value::NumericValue,
+    };
 
     #[test]
     fn can_parse_function() {
@@ -113,4 +158,118 @@ mod tests {
         assert_eq!(function_definition.name, "foo");
         assert_eq!(function_definition.variable_names, vec!["a"]);
     }
+
+    #[test]
+    fn can_parse_function_without_parameters() {
+        let result = parse_function(r#"function foo() { return 1; }"#);
+        assert!(result.is_ok());
+        let (remaining, function_definition) = result.unwrap();
+        assert_eq!(remaining, "");
+        assert_eq!(function_definition.variable_names.len(), 0);
+    }
+
+    #[test]
+    fn function_calls_produce_return_values() {
+        let (mut functions, mut variables) = empty_functions_and_variables();
+        let function = parse_function(
+            r#"function first_over(limit) {
+                for (i = 0; ; i++) {
+                    while (1) {
+                        if (i > limit) { return i; } else {};
+                        break;
+                    };
+                };
+            }"#,
+        )
+        .unwrap()
+        .1;
+        functions.insert(function.name.clone(), function);
+        let function = parse_function(r#"function nothing() { return; }"#)
+            .unwrap()
+            .1;
+        functions.insert(function.name.clone(), function);
+        let mut context = MutableContext::for_variables(&mut variables);
+
+        let result = parse_expression("first_over(3) + 1");
+        assert!(result.is_ok());
+        assert_eq!(
+            result.unwrap().1.evaluate(&functions, &mut context),
+            Value::Numeric(NumericValue::Integer(5)),
+        );
+
+        let result = parse_expression("nothing()");
+        assert!(result.is_ok());
+        assert_eq!(
+            result.unwrap().1.evaluate(&functions, &mut context),
+            Value::Uninitialized,
+        );
+    }
+
+    #[test]
+    fn arrays_are_passed_by_reference() {
+        let (mut functions, mut variables) = empty_functions_and_variables();
+        for definition in vec![
+            r#"function fill(arr, n) {
+                for (i = 1; i <= n; i++) { arr[i] = i * 10; };
+                n = 0;
+            }"#,
+            r#"function fill_twice(arr) {
+                fill(arr, 2);
+            }"#,
+        ] {
+            let function = parse_function(definition).unwrap().1;
+            functions.insert(function.name.clone(), function);
+        }
+        variables.assign_variable("count", Value::Numeric(NumericValue::Integer(3)));
+        let mut context = MutableContext::for_variables(&mut variables);
+
+        // Scalars are copied, while the existing array is mutated in place
+        context.assign_array_element("existing", "0", Value::Uninitialized);
+        let result = parse_expression("fill(existing, count)");
+        assert!(result.is_ok());
+        result.unwrap().1.evaluate(&functions, &mut context);
+        assert_eq!(context.array_keys("existing").len(), 4);
+        assert_eq!(
+            context.fetch_array_element("existing", "3"),
+            Value::Numeric(NumericValue::Integer(30)),
+        );
+        assert_eq!(
+            context.fetch_variable("count"),
+            Value::Numeric(NumericValue::Integer(3)),
+        );
+
+        // Untyped variables become arrays, even through nested calls
+        let result = parse_expression("fill_twice(fresh)");
+        assert!(result.is_ok());
+        result.unwrap().1.evaluate(&functions, &mut context);
+        assert_eq!(context.array_keys("fresh").len(), 2);
+        assert_eq!(
+            context.fetch_array_element("fresh", "2"),
+            Value::Numeric(NumericValue::Integer(20)),
+        );
+    }
+
+    #[test]
+    fn exiting_from_a_function_stops_the_statement_right_away() {
+        let (mut functions, mut variables) = empty_functions_and_variables();
+        let function = parse_function(r#"function stop() { exit 3; }"#).unwrap().1;
+        functions.insert(function.name.clone(), function);
+        let mut context = MutableContext::for_variables(&mut variables);
+
+        for text in vec![
+            r#"{ if (!stop()) print "bad"; print "after"; }"#,
+            r#"{ for (;; stop()) ; print "after"; }"#,
+            r#"{ while (stop() || 1) print "bad"; }"#,
+            r#"{ print "bad", stop(); }"#,
+        ] {
+            let action = parse_action(text).unwrap().1;
+            assert_eq!(
+                action.output_for_line(&functions, &mut context),
+                ControlFlow::Exit(Some(3)),
+                "{}",
+                text
+            );
+        }
+        assert_eq!(context.take_output(), Vec::<String>::new());
+    }
 }
@@ -1,54 +1,101 @@
-use nom::{character::complete::multispace0, combinator::map, sequence::tuple, IResult};
+use nom::{
+    branch::alt,
+    combinator::{map, opt, peek, verify},
+    sequence::{separated_pair, terminated},
+    IResult,
+};
+use std::cell::Cell;
 
 use crate::{
-    action::{parse_action, Action},
+    action::{parse_action, Action, JumpScope},
     context::MutableContext,
+    control_flow::ControlFlow,
     function::Functions,
+    parse_utilities::{blank, item_end},
     pattern::{parse_item_pattern, Pattern},
-    printable::Printable,
 };
 
 pub(crate) struct Item {
     pattern: Pattern,
     action: Action,
+    // Whether a range pattern is between its start and stop records
+    in_range: Cell<bool>,
 }
 
 impl Item {
+    pub(crate) fn is_begin(&self) -> bool {
+        matches!(self.pattern, Pattern::Begin)
+    }
+
+    pub(crate) fn check_jumps(&self) -> Result<(), &'static str> {
+        self.action.check_jumps(JumpScope {
+            in_loop: false,
+            in_function: false,
+        })
+    }
+
     pub(crate) fn output_for_line<'a>(
         &self,
         functions: &Functions,
         context: &mut MutableContext<'a>,
-    ) -> Printable<()> {
-        self.pattern
-            .matches(functions, context)
-            .and_then(|matched| {
-                if matched {
-                    self.action.output_for_line(functions, context)
-                } else {
-                    Printable::wrap(())
-                }
-            })
+    ) -> ControlFlow {
+        let matched = self.pattern.matches(&self.in_range, functions, context);
+        match context.take_interrupted_flow() {
+            Some(flow) => flow,
+            None if matched => self.action.output_for_line(functions, context),
+            None => ControlFlow::Normal,
+        }
     }
 
     pub(crate) fn output_for_begin<'a>(
         &self,
         functions: &Functions,
         context: &mut MutableContext<'a>,
-    ) -> Printable<()> {
+    ) -> ControlFlow {
         if let Pattern::Begin = self.pattern {
             self.action.output_for_line(functions, context)
         } else {
-            Printable::wrap(())
+            ControlFlow::Normal
+        }
+    }
+
+    pub(crate) fn output_for_end<'a>(
+        &self,
+        functions: &Functions,
+        context: &mut MutableContext<'a>,
+    ) -> ControlFlow {
+        if let Pattern::End = self.pattern {
+            self.action.output_for_line(functions, context)
+        } else {
+            ControlFlow::Normal
         }
     }
 }
 
+/// Either the pattern or the action may be left out, but not both. A missing pattern matches
+/// every record, and a missing action prints the record.
 pub(crate) fn parse_item(input: &str) -> IResult<&str, Item> {
+    let parse_pattern_only = terminated(
+        verify(parse_item_pattern, |pattern| {
+            !matches!(pattern, Pattern::Begin | Pattern::End)
+        }),
+        peek(item_end),
+    );
+
     map(
-        tuple((parse_item_pattern, multispace0, parse_action)),
-        |(pattern, _, action)| Item {
+        alt((
+            map(
+                separated_pair(opt(parse_item_pattern), blank, parse_action),
+                |(pattern, action)| (pattern.unwrap_or(Pattern::MatchEverything), action),
+            ),
+            map(parse_pattern_only, |pattern| {
+                (pattern, Action::print_record())
+            }),
+        )),
+        |(pattern, action)| Item {
             pattern: pattern,
             action: action,
+            in_range: Cell::new(false),
         },
     )(input)
 }
@@ -67,35 +114,58 @@ mod tests {
 
         let result = parse_item(r#"$1 ~ "hello" { print($0); }"#);
         assert!(result.is_ok());
-        assert_eq!(
-            result
-                .unwrap()
-                .1
-                .output_for_line(&functions, &mut context)
-                .output,
-            vec!["hello world today"],
-        );
+        result.unwrap().1.output_for_line(&functions, &mut context);
+        assert_eq!(context.take_output(), vec!["hello world today\n"]);
 
         let result = parse_item(r#"$2 ~ "hello" { print($0); }"#);
         assert!(result.is_ok());
-        assert_eq!(
-            result
-                .unwrap()
-                .1
-                .output_for_line(&functions, &mut context)
-                .output,
-            empty_string_vec,
-        );
+        result.unwrap().1.output_for_line(&functions, &mut context);
+        assert_eq!(context.take_output(), empty_string_vec);
 
         let result = parse_item(r#"11 ~ 1 { print($3); }"#);
         assert!(result.is_ok());
-        assert_eq!(
-            result
-                .unwrap()
-                .1
-                .output_for_line(&functions, &mut context)
-                .output,
-            vec!["today"],
-        );
+        result.unwrap().1.output_for_line(&functions, &mut context);
+        assert_eq!(context.take_output(), vec!["today\n"]);
+    }
+
+    #[test]
+    fn range_patterns_include_start_and_stop_records() {
+        let (functions, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+
+        let item = parse_item(r#"$1 == "start", $1 == "stop" { print($2); }"#)
+            .unwrap()
+            .1;
+        for line in vec![
+            "before 1", "start 2", "middle 3", "stop 4", "after 5", "start 6",
+        ] {
+            context.set_record_with_line(line);
+            item.output_for_line(&functions, &mut context);
+        }
+        assert_eq!(context.take_output(), vec!["2\n", "3\n", "4\n", "6\n"]);
+
+        // A record matching both ends is a range on its own
+        let item = parse_item(r#"/a/, /b/ { print($0); }"#).unwrap().1;
+        for line in vec!["ab", "c", "a", "c", "b", "c"] {
+            context.set_record_with_line(line);
+            item.output_for_line(&functions, &mut context);
+        }
+        assert_eq!(context.take_output(), vec!["ab\n", "a\n", "c\n", "b\n"]);
+    }
+
+    #[test]
+    fn test_end_item_only_runs_at_end() {
+        let (functions, mut variables) = empty_functions_and_variables();
+        let mut context = MutableContext::for_variables(&mut variables);
+        context.set_record_with_line("last line");
+        let empty_string_vec: Vec<&'static str> = vec![];
+
+        let item = parse_item(r#"END { print($2); }"#).unwrap().1;
+        item.output_for_line(&functions, &mut context);
+        assert_eq!(context.take_output(), empty_string_vec);
+        item.output_for_begin(&functions, &mut context);
+        assert_eq!(context.take_output(), empty_string_vec);
+        item.output_for_end(&functions, &mut context);
+        assert_eq!(context.take_output(), vec!["line\n"]);
     }
 }
@@ -3,29 +3,36 @@ extern crate regex;
 
 mod action;
 mod context;
+mod control_flow;
 mod expression;
+mod format;
 mod function;
 mod item;
 mod parse_args;
+mod parse_utilities;
 mod pattern;
-mod printable;
 mod program;
 mod program_run;
+mod runtime_error;
 mod value;
 
+use std::io::Write;
+
 use crate::program::parse_program;
 
 pub use program_run::ProgramRun;
 
-pub fn start_run(args: Vec<String>) -> (ProgramRun, Vec<String>) {
+/// Output is written to `output` as the program produces it, while input is read from the files
+/// named in `args`, or standard input without any
+pub fn start_run(args: Vec<String>, output: Box<dyn Write>) -> ProgramRun {
     let (program_string, parsed_args) = parse_args::parse_args(args);
     let program = parse_program(&program_string);
 
-    let mut run = ProgramRun::new_for_program(program);
+    let mut run = ProgramRun::new_for_program(program, output);
 
     run.apply_args(&parsed_args);
 
-    (run, parsed_args.filepaths_to_parse)
+    run
 }
 
 #[cfg(test)]
@@ -36,4 +43,12 @@ mod test_utilities {
     pub(crate) fn empty_functions_and_variables() -> (function::Functions, context::Variables) {
         (HashMap::new(), context::Variables::empty())
     }
+
+    /// Evaluates an expression which must use up all of `text`, without any user functions
+    pub(crate) fn evaluate(text: &str, context: &mut context::MutableContext) -> value::Value {
+        let (functions, _) = empty_functions_and_variables();
+        let (remaining, expression) = expression::parse_expression(text).unwrap();
+        assert_eq!(remaining, "", "{}", text);
+        expression.evaluate(&functions, context)
+    }
 }
@@ -0,0 +1,85 @@
+use nom::{
+    branch::alt,
+    bytes::complete::{tag, take_while1},
+    character::complete::{anychar, multispace1, not_line_ending, one_of},
+    combinator::{not, opt, peek, recognize},
+    multi::{many0_count, many1_count},
+    sequence::{pair, preceded, terminated, tuple},
+    IResult,
+};
+
+/// Spaces, tabs, and backslash-newline continuations, but never a bare newline
+///
+/// Newlines end statements, so this is the whitespace allowed between the tokens of a
+/// single statement or expression.
+pub(crate) fn blank(input: &str) -> IResult<&str, &str> {
+    recognize(many0_count(alt((
+        take_while1(|c: char| c == ' ' || c == '\t' || c == '\r'),
+        tag("\\\n"),
+        tag("\\\r\n"),
+    ))))(input)
+}
+
+/// A `#` comment, running up to (but not including) the end of the line
+fn comment(input: &str) -> IResult<&str, &str> {
+    recognize(pair(tag("#"), not_line_ending))(input)
+}
+
+/// Any amount of whitespace, newlines, and comments
+pub(crate) fn newline_opt(input: &str) -> IResult<&str, &str> {
+    recognize(many0_count(alt((multispace1, comment, tag("\\\n")))))(input)
+}
+
+/// One or more semicolons or newlines, along with the blank lines and comments after them
+pub(crate) fn terminator(input: &str) -> IResult<&str, &str> {
+    recognize(pair(
+        many1_count(preceded(pair(blank, opt(comment)), one_of(";\n"))),
+        newline_opt,
+    ))(input)
+}
+
+/// The end of a statement: a terminator, or the `}` closing its action
+pub(crate) fn statement_end(input: &str) -> IResult<&str, &str> {
+    alt((terminator, recognize(pair(blank, peek(tag("}"))))))(input)
+}
+
+/// The end of an item without an action: a terminator, or the end of the program
+pub(crate) fn item_end(input: &str) -> IResult<&str, &str> {
+    alt((
+        terminator,
+        recognize(tuple((blank, opt(comment), not(anychar)))),
+    ))(input)
+}
+
+/// Matches a keyword only when it isn't the prefix of a longer name, e.g. `next` in `nextfile`
+pub(crate) fn keyword<'a>(word: &'static str) -> impl Fn(&'a str) -> IResult<&'a str, &'a str> {
+    terminated(
+        tag(word),
+        not(peek(take_while1(|c: char| {
+            c.is_ascii_alphanumeric() || c == '_'
+        }))),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blank_stops_at_newlines() {
+        assert_eq!(blank(" \t\\\n  x"), Ok(("x", " \t\\\n  ")));
+        assert_eq!(blank("  \n x"), Ok(("\n x", "  ")));
+    }
+
+    #[test]
+    fn terminators_include_comments_and_blank_lines() {
+        assert_eq!(
+            terminator("; # note\n\n  ;\n x"),
+            Ok(("x", "; # note\n\n  ;\n "))
+        );
+        assert_eq!(terminator(" # note\n}"), Ok(("}", " # note\n")));
+        assert!(terminator(" }").is_err());
+        assert_eq!(statement_end(" }"), Ok(("}", " ")));
+        assert_eq!(item_end(" # note"), Ok(("", " # note")));
+    }
+}
@@ -1,53 +1,77 @@
-use nom::{
-    branch::alt,
-    bytes::complete::tag,
-    combinator::{map, opt},
-    IResult,
-};
+use nom::{branch::alt, character::complete::one_of, combinator::map, sequence::tuple, IResult};
+use std::cell::Cell;
 
 use crate::{
     context::MutableContext,
     expression::{parse_expression, Expression},
     function::Functions,
-    printable::Printable,
+    parse_utilities::{blank, keyword, newline_opt},
 };
 
 pub(crate) enum Pattern {
     MatchEverything,
     Expression(Box<dyn Expression>),
+    Range(Box<dyn Expression>, Box<dyn Expression>),
     Begin,
     End,
 }
 
 impl Pattern {
+    /// `in_range` is owned by the item, and tracks whether a range pattern has started matching
+    /// without having stopped yet
     pub(crate) fn matches(
         &self,
+        in_range: &Cell<bool>,
         functions: &Functions,
         context: &mut MutableContext,
-    ) -> Printable<bool> {
+    ) -> bool {
         match self {
-            Pattern::MatchEverything => Printable::wrap(true),
-            Pattern::Expression(expression) => match expression.regex() {
-                Some(regex) => {
-                    Printable::wrap(regex.is_match(&context.fetch_field(0).coerce_to_string()))
+            Pattern::MatchEverything => true,
+            Pattern::Expression(expression) => {
+                expression_matches(expression.as_ref(), functions, context)
+            }
+            Pattern::Range(start, stop) => {
+                if !in_range.get() && !expression_matches(start.as_ref(), functions, context) {
+                    return false;
                 }
-                None => expression
-                    .evaluate(functions, context)
-                    .map(|value| value.coercion_to_boolean()),
-            },
-            Pattern::Begin => Printable::wrap(false),
-            Pattern::End => Printable::wrap(false),
+                // The record which starts the range may also stop it
+                let stopped = expression_matches(stop.as_ref(), functions, context);
+                in_range.set(!stopped);
+                true
+            }
+            Pattern::Begin => false,
+            Pattern::End => false,
         }
     }
 }
 
+fn expression_matches(
+    expression: &dyn Expression,
+    functions: &Functions,
+    context: &mut MutableContext,
+) -> bool {
+    match expression.regex() {
+        Some(regex) => regex.is_match(&context.fetch_field(0).coerce_to_string()),
+        None => expression
+            .evaluate(functions, context)
+            .coercion_to_boolean(),
+    }
+}
+
 pub(crate) fn parse_item_pattern(input: &str) -> IResult<&str, Pattern> {
-    let parse_pattern = alt((
-        map(tag("BEGIN"), |_| Pattern::Begin),
-        map(tag("END"), |_| Pattern::End),
+    alt((
+        map(keyword("BEGIN"), |_| Pattern::Begin),
+        map(keyword("END"), |_| Pattern::End),
+        map(
+            tuple((
+                parse_expression,
+                blank,
+                one_of(","),
+                newline_opt,
+                parse_expression,
+            )),
+            |(start, _, _, _, stop)| Pattern::Range(start, stop),
+        ),
         map(parse_expression, |expr| Pattern::Expression(expr)),
-    ));
-    map(opt(parse_pattern), |pattern_opt| {
-        pattern_opt.unwrap_or(Pattern::MatchEverything)
-    })(input)
+    ))(input)
 }
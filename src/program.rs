@@ -1,9 +1,8 @@
 use nom::{
     branch::alt,
-    character::complete::multispace0,
-    combinator::{all_consuming, map},
-    multi::many1,
-    sequence::delimited,
+    combinator::{all_consuming, map, opt},
+    multi::many0,
+    sequence::{pair, preceded, terminated},
     IResult,
 };
 use std::collections::HashMap;
@@ -11,6 +10,7 @@ use std::collections::HashMap;
 use crate::{
     function::{parse_function, FunctionDefinition, Functions},
     item::{parse_item, Item},
+    parse_utilities::{newline_opt, terminator},
 };
 
 pub(super) struct Program {
@@ -18,6 +18,13 @@ pub(super) struct Program {
     pub(crate) functions: Functions,
 }
 
+impl Program {
+    /// A program made only of BEGIN items is done once they have run, without reading any input
+    pub(crate) fn reads_input(&self) -> bool {
+        self.items.iter().any(|item| !item.is_begin())
+    }
+}
+
 enum ParsedThing {
     Item(Item),
     Function(FunctionDefinition),
@@ -25,12 +32,15 @@ enum ParsedThing {
 
 fn parse_item_list(input: &str) -> IResult<&str, (Vec<Item>, Vec<FunctionDefinition>)> {
     let parse_thing = alt((
-        map(parse_item, |item| ParsedThing::Item(item)),
         map(parse_function, |function| ParsedThing::Function(function)),
+        map(parse_item, ParsedThing::Item),
     ));
 
     map(
-        many1(delimited(multispace0, parse_thing, multispace0)),
+        preceded(
+            newline_opt,
+            many0(terminated(parse_thing, pair(opt(terminator), newline_opt))),
+        ),
         |things| {
             let mut items = vec![];
             let mut functions = vec![];
@@ -54,6 +64,16 @@ fn parse_item_list(input: &str) -> IResult<&str, (Vec<Item>, Vec<FunctionDefinit
 pub(super) fn parse_program(program_text: &str) -> Program {
     match all_consuming(parse_item_list)(program_text) {
         Ok((_, (items, functions))) => {
+            // Stray jumps can only be found once each item and function has been parsed whole
+            let jumps = items
+                .iter()
+                .map(Item::check_jumps)
+                .chain(functions.iter().map(FunctionDefinition::check_jumps))
+                .collect::<Result<(), _>>();
+            if let Err(e) = jumps {
+                panic!("Could not parse! {}", e);
+            }
+
             let mut function_map = HashMap::new();
             for func in functions {
                 function_map.insert(func.name.clone(), func);
@@ -114,4 +134,52 @@ mod tests {
         assert_eq!(program.items.len(), 1);
         assert_eq!(program.functions.len(), 1);
     }
+
+    #[test]
+    fn items_may_leave_out_patterns_or_actions() {
+        let program = parse_program(
+            r#"
+# comments and blank lines are ignored
+NR > 1
+/foo/ { print }; BEGIN { x = 1 }{ print x }
+function f(a,
+           b) { return a }
+END { print "done" }"#,
+        );
+        assert_eq!(program.items.len(), 5);
+        assert_eq!(program.functions.len(), 1);
+
+        assert_eq!(parse_program("").items.len(), 0);
+    }
+
+    #[test]
+    fn jumps_are_allowed_where_they_are_handled() {
+        // Assert no panic
+        parse_program(
+            r#"function f(x) {
+            while (x) { if (x > 1) break; else continue }
+            do { for (k in a) break } while (0)
+            return x
+        }
+        { for (;;) { if (f($1)) { break } } }"#,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "break and continue can only be used inside of a loop")]
+    fn break_outside_of_a_loop_is_rejected() {
+        parse_program("BEGIN { if (1) { break } }");
+    }
+
+    #[test]
+    #[should_panic(expected = "break and continue can only be used inside of a loop")]
+    fn continue_outside_of_a_loop_in_a_function_is_rejected() {
+        parse_program("function f() { continue }");
+    }
+
+    #[test]
+    #[should_panic(expected = "return can only be used inside of a function")]
+    fn return_outside_of_a_function_is_rejected() {
+        parse_program("{ while (1) return }");
+    }
 }
@@ -1,9 +1,10 @@
-use std::io::{BufRead, BufReader, Read, Stdin};
+use std::io::Write;
 
 use crate::{
-    context::{MutableContext, VariableStore, Variables},
+    context::{MainInput, MutableContext, VariableStore, Variables},
+    control_flow::ControlFlow,
+    item::Item,
     parse_args,
-    printable::Printable,
     program::Program,
     value::{NumericValue, Value},
 };
@@ -11,103 +12,322 @@ use crate::{
 pub struct ProgramRun {
     program: Program,
     variables: Variables,
-}
-
-type IOResult = std::io::Result<usize>;
-
-pub trait LineReadable {
-    fn trait_read_line(&mut self, buffer: &mut String) -> IOResult;
-}
-
-impl LineReadable for Stdin {
-    fn trait_read_line(&mut self, buffer: &mut String) -> IOResult {
-        self.read_line(buffer)
-    }
-}
-
-impl<T: Read> LineReadable for BufReader<T> {
-    fn trait_read_line(&mut self, buffer: &mut String) -> IOResult {
-        self.read_line(buffer)
-    }
+    exit_status: Option<i32>,
+    input: MainInput,
+    // Standard output, written to as the program runs
+    output: Box<dyn Write>,
 }
 
 impl ProgramRun {
-    pub(crate) fn new_for_program(program: Program) -> ProgramRun {
+    pub(crate) fn new_for_program(program: Program, output: Box<dyn Write>) -> ProgramRun {
         ProgramRun {
             program: program,
             variables: Variables::empty(),
+            exit_status: None,
+            input: MainInput::for_paths(vec![]),
+            output: output,
         }
     }
 
-    pub fn process_file<LR: LineReadable>(&mut self, reader: &mut LR) -> Vec<String> {
-        self.variables
-            .assign_variable("FNR", Value::Numeric(NumericValue::Integer(0)));
-
-        let mut buffer = String::new();
-        let mut output = vec![];
-        loop {
-            match reader.trait_read_line(&mut buffer) {
-                Ok(n) => {
-                    if n == 0 {
-                        break;
-                    }
-                    if buffer.chars().last().unwrap() == '\n' {
-                        buffer.truncate(n - 1);
-                    }
-                    output.append(&mut self.output_for_line(&buffer));
-                    buffer.clear();
-                }
-                Err(error) => {
-                    eprintln!("Error encountered: {}", error);
-                    break;
-                }
+    pub fn has_exited(&self) -> bool {
+        self.exit_status.is_some()
+    }
+
+    pub fn exit_status(&self) -> i32 {
+        self.exit_status.unwrap_or(0)
+    }
+
+    /// Output for each record is flushed as soon as the record has been processed
+    pub fn process_input(&mut self) {
+        if !self.program.reads_input() {
+            return;
+        }
+        while !self.has_exited() {
+            match self.input.read_record(&mut self.variables) {
+                Ok(None) => break,
+                Ok(Some(line)) => match self.output_for_line(&line) {
+                    ControlFlow::Exit(status) => self.record_exit(status),
+                    ControlFlow::NextFile => self.input.skip_file(),
+                    _ => {}
+                },
+                Err(error) => eprintln!("Error encountered: {}", error),
             }
         }
-        output
     }
 
-    fn output_for_line(&mut self, line: &str) -> Vec<String> {
+    /// `getline` can keep reading records from the main input while the items run
+    fn output_for_line(&mut self, line: &str) -> ControlFlow {
         // Need explicit borrow of the variables to avoid borrowing `self` later
         let functions = &self.program.functions;
         self.variables.increment_variable("NR");
         self.variables.increment_variable("FNR");
-        let mut context = MutableContext::for_variables(&mut self.variables);
+        let mut context = MutableContext::for_variables(&mut self.variables)
+            .reading_from(&mut self.input)
+            .writing_to(&mut *self.output);
         context.set_record_with_line(line);
 
-        self.program
-            .items
-            .iter()
-            .fold(Printable::wrap(()), |result, item| {
-                result.and_then(|_| item.output_for_line(functions, &mut context))
-            })
-            .output
+        let flow = output_for_items(&self.program.items, |item| {
+            item.output_for_line(functions, &mut context)
+        });
+        context.flush_output();
+        flow
     }
 
-    pub fn output_for_begin_items(&mut self) -> Vec<String> {
+    /// Plain `getline` in the BEGIN items reads records from the main input
+    pub fn output_for_begin_items(&mut self) {
         let variables = &mut self.variables;
         let functions = &self.program.functions;
-        let mut context = MutableContext::for_variables(variables);
+        let mut context = MutableContext::for_variables(variables)
+            .reading_from(&mut self.input)
+            .writing_to(&mut *self.output);
 
-        self.program
-            .items
-            .iter()
-            .fold(Printable::wrap(()), |result, item| {
-                result.and_then(|_| item.output_for_begin(functions, &mut context))
-            })
-            .output
+        let flow = output_for_items(&self.program.items, |item| {
+            item.output_for_begin(functions, &mut context)
+        });
+        context.flush_output();
+        if let ControlFlow::Exit(status) = flow {
+            self.record_exit(status);
+        }
+    }
+
+    pub fn output_for_end_items(&mut self) {
+        let variables = &mut self.variables;
+        let functions = &self.program.functions;
+        // The last record read is still held in the variables, as POSIX expects for END items
+        let mut context = MutableContext::for_variables(variables).writing_to(&mut *self.output);
+
+        let flow = output_for_items(&self.program.items, |item| {
+            item.output_for_end(functions, &mut context)
+        });
+        context.flush_output();
+        if let ControlFlow::Exit(status) = flow {
+            self.record_exit(status);
+        }
+    }
+
+    /// Output to redirected files and commands is only complete once their streams are closed
+    pub fn close_streams(&mut self) {
+        self.variables.close_streams();
+    }
+
+    fn record_exit(&mut self, status: Option<i32>) {
+        // A bare `exit` keeps the status from any earlier `exit` (e.g. one that triggered END)
+        self.exit_status = Some(status.or(self.exit_status).unwrap_or(0));
     }
 
     pub(super) fn apply_args(&mut self, args: &parse_args::Args) {
         self.variables
             .assign_variable("FS", Value::String(args.field_separator.clone()));
+        // ARGV[0] names the interpreter, and the operands follow it
         self.variables.assign_variable(
             "ARGC",
-            Value::Numeric(NumericValue::Integer(args.filepaths_to_parse.len() as i64)),
+            Value::Numeric(NumericValue::Integer(
+                args.filepaths_to_parse.len() as i64 + 1,
+            )),
         );
+        self.variables
+            .assign_array_element("ARGV", "0", Value::String("rust-awk".to_string()));
+        for (index, operand) in args.filepaths_to_parse.iter().enumerate() {
+            self.variables.assign_array_element(
+                "ARGV",
+                &(index + 1).to_string(),
+                Value::from_input(operand.clone()),
+            );
+        }
+        for (name, value) in std::env::vars_os() {
+            self.variables.assign_array_element(
+                "ENVIRON",
+                &name.to_string_lossy(),
+                Value::from_input(value.to_string_lossy().into_owned()),
+            );
+        }
+        self.input = MainInput::for_paths(args.filepaths_to_parse.clone());
 
         for (name, value) in args.variables.iter() {
             self.variables
-                .assign_variable(name, Value::String(value.to_string()));
+                .assign_variable(name, Value::from_input(value.to_string()));
+        }
+    }
+}
+
+/// Evaluates items in order until one of them interrupts the normal flow of execution
+fn output_for_items<F>(items: &Vec<Item>, mut output_for_item: F) -> ControlFlow
+where
+    F: FnMut(&Item) -> ControlFlow,
+{
+    for item in items {
+        match output_for_item(item) {
+            ControlFlow::Normal => {}
+            ControlFlow::Break | ControlFlow::Continue | ControlFlow::Return(_) => {
+                unreachable!("break, continue, and return are only allowed where they are handled")
+            }
+            flow => return flow,
+        }
+    }
+    ControlFlow::Normal
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::LineReadable;
+    use crate::program::parse_program;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[derive(Clone)]
+    struct SharedOutput(Rc<RefCell<Vec<u8>>>);
+
+    impl SharedOutput {
+        fn text(&self) -> String {
+            String::from_utf8(self.0.borrow().clone()).unwrap()
+        }
+    }
+
+    impl Write for SharedOutput {
+        fn write(&mut self, buffer: &[u8]) -> std::io::Result<usize> {
+            self.0.borrow_mut().extend_from_slice(buffer);
+            Ok(buffer.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Notes down what had been written when each line was asked for
+    struct WatchedInput {
+        lines: Vec<&'static str>,
+        output: SharedOutput,
+        seen: Rc<RefCell<Vec<String>>>,
+    }
+
+    impl LineReadable for WatchedInput {
+        fn trait_read_line(&mut self, buffer: &mut String) -> std::io::Result<usize> {
+            self.seen.borrow_mut().push(self.output.text());
+            if self.lines.is_empty() {
+                return Ok(0);
+            }
+            let line = self.lines.remove(0);
+            buffer.push_str(line);
+            Ok(line.len())
+        }
+    }
+
+    fn run_with_input(
+        program: &str,
+        lines: Vec<&'static str>,
+    ) -> (ProgramRun, SharedOutput, Rc<RefCell<Vec<String>>>) {
+        let output = SharedOutput(Rc::new(RefCell::new(vec![])));
+        let seen = Rc::new(RefCell::new(vec![]));
+        let mut run = ProgramRun::new_for_program(parse_program(program), Box::new(output.clone()));
+        run.input = MainInput::for_reader(Box::new(WatchedInput {
+            lines: lines,
+            output: output.clone(),
+            seen: seen.clone(),
+        }));
+        (run, output, seen)
+    }
+
+    #[test]
+    fn output_is_written_as_each_record_is_processed() {
+        let (mut run, output, seen) = run_with_input(
+            r#"BEGIN { print "start" } { print $2 } END { print NR }"#,
+            vec!["a 1\n", "b 2\n"],
+        );
+
+        run.output_for_begin_items();
+        run.process_input();
+        assert_eq!(
+            *seen.borrow(),
+            vec!["start\n", "start\n1\n", "start\n1\n2\n"]
+        );
+
+        run.output_for_end_items();
+        assert_eq!(output.text(), "start\n1\n2\n2\n");
+    }
+
+    #[test]
+    fn getline_in_begin_reads_from_the_main_input() {
+        let (mut run, output, _) = run_with_input(
+            r#"BEGIN { getline; print "begin", $0 } { print FNR, $0 }"#,
+            vec!["a\n", "b\n"],
+        );
+
+        run.output_for_begin_items();
+        run.process_input();
+        assert_eq!(output.text(), "begin a\n2 b\n");
+    }
+
+    #[test]
+    fn getline_reads_on_into_the_next_file() {
+        let paths: Vec<String> = vec![("first", "a\nb\n"), ("second", "c\n")]
+            .into_iter()
+            .map(|(name, text)| {
+                let path = std::env::temp_dir().join(format!("rust_awk_main_input_{}.txt", name));
+                std::fs::write(&path, text).unwrap();
+                path.to_str().unwrap().to_string()
+            })
+            .collect();
+        let output = SharedOutput(Rc::new(RefCell::new(vec![])));
+        let program = parse_program(
+            r#"NR == 1 { while ((getline line) > 0) print line, NR, FNR } END { print $0 }"#,
+        );
+        let mut run = ProgramRun::new_for_program(program, Box::new(output.clone()));
+        run.input = MainInput::for_paths(paths.clone());
+
+        run.process_input();
+        run.output_for_end_items();
+        assert_eq!(output.text(), "b 2 2\nc 3 1\na\n");
+
+        for path in paths {
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn programs_with_only_begin_items_read_no_input() {
+        let (mut run, output, seen) = run_with_input(r#"BEGIN { print "only" }"#, vec!["a\n"]);
+
+        run.output_for_begin_items();
+        run.process_input();
+        run.output_for_end_items();
+        assert!(seen.borrow().is_empty());
+        assert_eq!(output.text(), "only\n");
+    }
+
+    #[test]
+    fn operands_and_the_environment_are_numeric_strings() {
+        let (program, args) = parse_args::parse_args(vec![
+            "{ print }".to_string(),
+            "input.txt".to_string(),
+            "10".to_string(),
+        ]);
+        let mut run = ProgramRun::new_for_program(parse_program(&program), Box::new(vec![]));
+        run.apply_args(&args);
+
+        let variables = &mut run.variables;
+        assert_eq!(
+            variables.fetch_variable("ARGC"),
+            Value::Numeric(NumericValue::Integer(3))
+        );
+        assert_eq!(
+            variables.fetch_array_element("ARGV", "0"),
+            Value::String("rust-awk".to_string())
+        );
+        assert_eq!(
+            variables.fetch_array_element("ARGV", "1"),
+            Value::String("input.txt".to_string())
+        );
+        assert_eq!(
+            variables.fetch_array_element("ARGV", "2"),
+            Value::StrNum("10".to_string())
+        );
+        for (name, value) in std::env::vars() {
+            assert_eq!(
+                variables.fetch_array_element("ENVIRON", &name),
+                Value::from_input(value)
+            );
         }
     }
 }
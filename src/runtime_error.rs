@@ -0,0 +1,23 @@
+use regex::Regex;
+use std::io::{stdout, Write};
+
+/// Stops the run for an error such as dividing by zero or output that can't be written, with a
+/// message and a non-zero exit status rather than a Rust panic and backtrace.
+///
+/// Tests can't survive the process exiting, so there it panics with the message instead.
+pub(crate) fn runtime_error(message: &str) -> ! {
+    if cfg!(test) {
+        panic!("{}", message);
+    }
+    // Anything already printed should still come out ahead of the error
+    let _ = stdout().flush();
+    eprintln!("rust-awk: {}", message);
+    std::process::exit(2);
+}
+
+/// Regexes built from strings while running, as in `$0 ~ pattern`, may turn out not to compile
+pub(crate) fn compile_regex(pattern: &str) -> Regex {
+    Regex::new(pattern).unwrap_or_else(|error| {
+        runtime_error(&format!("regular expression compile failed: {}", error))
+    })
+}
@@ -1,6 +1,8 @@
 use nom::{character::complete::multispace0, re_find, sequence::preceded, IResult};
 use regex::Regex;
 
+use crate::format::sprintf;
+
 #[derive(PartialEq, Debug, Copy, Clone)]
 pub(crate) enum NumericValue {
     Integer(i64),
@@ -10,18 +12,54 @@ pub(crate) enum NumericValue {
 #[derive(PartialEq, Debug)]
 pub(crate) enum Value {
     String(String),
+    /// Input from outside the program, such as a field, which looks like a number. It keeps its
+    /// original text, but compares as a number.
+    StrNum(String),
     Numeric(NumericValue),
     Uninitialized,
 }
 
 pub(crate) static UNINITIALIZED_VALUE: Value = Value::Uninitialized;
 
+/// The default value of both CONVFMT and OFMT
+pub(crate) const DEFAULT_NUMBER_FORMAT: &str = "%.6g";
+
 impl Value {
+    /// Fields, `getline` input, and the like are numeric strings when they look like numbers
+    pub(crate) fn from_input(text: String) -> Value {
+        if looks_numeric(&text) {
+            Value::StrNum(text)
+        } else {
+            Value::String(text)
+        }
+    }
+
+    /// Comparisons are numeric only when neither side is a string
+    pub(crate) fn compares_as_number(&self) -> bool {
+        match self {
+            Value::String(_) => false,
+            Value::StrNum(_) | Value::Numeric(_) | Value::Uninitialized => true,
+        }
+    }
+
     pub(crate) fn coerce_to_string(&self) -> String {
+        self.coerce_to_string_with_format(DEFAULT_NUMBER_FORMAT)
+    }
+
+    /// Non-integral numbers are converted using `number_format` (i.e. CONVFMT or OFMT), while
+    /// integral values always convert as integers
+    pub(crate) fn coerce_to_string_with_format(&self, number_format: &str) -> String {
         match self {
-            Value::String(string) => string.clone(),
+            Value::String(string) | Value::StrNum(string) => string.clone(),
             Value::Numeric(NumericValue::Integer(i)) => i.to_string(),
-            Value::Numeric(NumericValue::Float(f)) => f.to_string(),
+            Value::Numeric(NumericValue::Float(f))
+                if f.fract() == 0.0 && f.abs() < i64::MAX as f64 =>
+            {
+                (*f as i64).to_string()
+            }
+            Value::Numeric(NumericValue::Float(_)) => {
+                sprintf(number_format, std::slice::from_ref(self))
+            }
             Value::Uninitialized => "".to_string(),
         }
     }
@@ -29,7 +67,7 @@ impl Value {
     pub(crate) fn coerce_to_numeric(&self) -> NumericValue {
         match self {
             Value::Numeric(n) => *n,
-            Value::String(s) => match preceded(multispace0, parse_numeric)(s) {
+            Value::String(s) | Value::StrNum(s) => match preceded(multispace0, parse_numeric)(s) {
                 Ok((_, n)) => n,
                 Err(_) => NumericValue::Float(0.0),
             },
@@ -37,12 +75,25 @@ impl Value {
         }
     }
 
+    /// For arithmetic which is only defined over floating point, like the math builtins
+    pub(crate) fn coerce_to_float(&self) -> f64 {
+        match self.coerce_to_numeric() {
+            NumericValue::Integer(i) => i as f64,
+            NumericValue::Float(f) => f,
+        }
+    }
+
     pub(crate) fn coercion_to_boolean(&self) -> bool {
         match self {
             Value::String(s) => match s.as_str() {
                 "" => false,
                 _ => true,
             },
+            Value::StrNum(_) => match self.coerce_to_numeric() {
+                NumericValue::Integer(0) => false,
+                NumericValue::Float(f) => f != 0.0,
+                _ => true,
+            },
             Value::Numeric(n) => match n {
                 NumericValue::Integer(0) => false,
                 NumericValue::Float(f) => *f != 0.0,
@@ -57,6 +108,7 @@ impl Clone for Value {
     fn clone(&self) -> Self {
         match self {
             Value::String(string) => Value::String(string.clone()),
+            Value::StrNum(string) => Value::StrNum(string.clone()),
             Value::Numeric(val) => Value::Numeric(*val),
             Value::Uninitialized => Value::Uninitialized,
         }
@@ -93,10 +145,7 @@ pub(crate) fn parse_numeric(input: &str) -> IResult<&str, NumericValue> {
             // If after simplification, the number has no decimal digits, then it is considered an
             // integer
             if exponent >= 0 {
-                IResult::Ok((
-                    input,
-                    NumericValue::Integer(matched.parse::<f64>().unwrap() as i64),
-                ))
+                IResult::Ok((input, parse_as_int(matched)))
             } else {
                 IResult::Ok((input, parse_as_float(matched)))
             }
@@ -112,10 +161,7 @@ pub(crate) fn parse_numeric(input: &str) -> IResult<&str, NumericValue> {
             // If after simplification, the number has no decimal digits, then it is considered an
             // integer
             if exponent >= num_decimals {
-                IResult::Ok((
-                    input,
-                    NumericValue::Integer(matched.parse::<f64>().unwrap() as i64),
-                ))
+                IResult::Ok((input, parse_as_int(matched)))
             } else {
                 IResult::Ok((input, parse_as_float(matched)))
             }
@@ -124,18 +170,57 @@ pub(crate) fn parse_numeric(input: &str) -> IResult<&str, NumericValue> {
     }
 }
 
+/// A number with optional surrounding blanks, e.g. ` -1.5e3 ` or `2.`, but not `0x1A` or `inf`
+fn looks_numeric(text: &str) -> bool {
+    let trimmed = text.trim();
+    let unsigned = trimmed
+        .strip_prefix(|c| c == '-' || c == '+')
+        .unwrap_or(trimmed);
+    unsigned.starts_with(|c: char| c.is_ascii_digit() || c == '.') && trimmed.parse::<f64>().is_ok()
+}
+
 fn parse_as_float(s: &str) -> NumericValue {
     NumericValue::Float(s.parse::<f64>().unwrap())
 }
 
+/// Whole numbers too large for an integer, like `1e300`, are kept as floats
 fn parse_as_int(s: &str) -> NumericValue {
-    NumericValue::Integer(s.parse::<f64>().unwrap() as i64)
+    let value = s.parse::<f64>().unwrap();
+    if value.abs() < i64::MAX as f64 {
+        NumericValue::Integer(value as i64)
+    } else {
+        NumericValue::Float(value)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_numeric_to_string() {
+        assert_eq!(
+            Value::Numeric(NumericValue::Float(1.0 / 3.0)).coerce_to_string(),
+            "0.333333",
+        );
+        assert_eq!(
+            Value::Numeric(NumericValue::Float(1.0 / 3.0)).coerce_to_string_with_format("%.2f"),
+            "0.33",
+        );
+        assert_eq!(
+            Value::Numeric(NumericValue::Float(12.0)).coerce_to_string_with_format("%.2f"),
+            "12",
+        );
+        assert_eq!(
+            Value::Numeric(NumericValue::Float(-1e30)).coerce_to_string(),
+            "-1e+30",
+        );
+        assert_eq!(
+            Value::Numeric(NumericValue::Integer(123456789)).coerce_to_string_with_format("%.2g"),
+            "123456789",
+        );
+    }
+
     #[test]
     fn test_string_to_numeric() {
         assert_eq!(
@@ -172,6 +257,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn input_which_looks_numeric_is_a_numeric_string() {
+        for text in vec!["10", " -1.5e3 ", "2.", "+.5", "0"] {
+            assert_eq!(
+                Value::from_input(text.to_string()),
+                Value::StrNum(text.to_string()),
+                "{}",
+                text
+            );
+        }
+        for text in vec!["", " ", "abc", "10abc", "0x1A", "inf", "-nan", "1e", "."] {
+            assert_eq!(
+                Value::from_input(text.to_string()),
+                Value::String(text.to_string()),
+                "{}",
+                text
+            );
+        }
+
+        assert_eq!(
+            Value::StrNum("0.0".to_string()).coercion_to_boolean(),
+            false
+        );
+        assert_eq!(Value::String("0.0".to_string()).coercion_to_boolean(), true);
+    }
+
     #[test]
     fn parse_number_literals() {
         // Integers
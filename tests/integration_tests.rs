@@ -96,3 +96,24 @@ fn all_integration_tests() {
         }
     }
 }
+
+#[test]
+fn stops_quietly_once_nothing_reads_the_output() {
+    let mut piped = Command::new("cargo")
+        .args(["run", "--bin", "rust-awk", r#"BEGIN { while (1) print "y" }"#])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to spawn");
+    {
+        let mut line = [0; 2];
+        let mut stdout = piped.stdout.take().expect("Failed to open StdOut");
+        std::io::Read::read_exact(&mut stdout, &mut line).expect("Failed to read");
+        assert_eq!(&line, b"y\n");
+    }
+
+    let output = piped.wait_with_output().expect("Failed to wait");
+    let errors = from_utf8(&output.stderr).expect("Failed to deserialize");
+    assert!(!errors.contains("panicked"), "{}", errors);
+    assert_eq!(output.status.code(), Some(141));
+}
@@ -0,0 +1,9 @@
+{ count[$1]++; pairs[$1, NR] = $2; }
+END {
+  print(count["a"], count["b"], ("c" in count), ("a" in count));
+  print(pairs["b", 3], (("a", 1) in pairs), (("a", 2) in pairs));
+  delete count["a"];
+  print(("a" in count), ("b" in count));
+  delete count;
+  print(("b" in count));
+}
@@ -0,0 +1,20 @@
+# Assignments are expressions which yield the value assigned
+BEGIN { total = count = 0 }
+{
+    count += 1
+    total += NF
+    words[NF] += 1
+    $1 = "#"
+    print $0
+}
+END {
+    n = count
+    while ((n = n - 1) > 0)
+        printf "%d ", n
+    printf "\n"
+    print "count", count, "total", total
+    total -= 1; total *= 3; total /= 2; total %= 7
+    print "total", total
+    x = 2; x ^= 10
+    print "x", x, (y = x) == 1024
+}
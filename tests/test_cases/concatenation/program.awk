@@ -0,0 +1,12 @@
+# Expressions next to each other are joined as strings
+{
+    line = line $1
+    print NR ": " $1 "-" $NF, length($0) " chars"
+    print NR -1, NR " " -1, (NR) (NR + 1)
+    seen[$1 "" NR] = 1
+}
+END {
+    print "joined " line
+    for (key in seen) count++
+    print count " keys", ("a" 1 in seen), ($1 NR in seen)
+}
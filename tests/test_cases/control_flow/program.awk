@@ -0,0 +1,18 @@
+NR == 1 { next; }
+{
+  i = 0;
+  while (1) {
+    i++;
+    if (i > 3) { break; } else {};
+    if (i == 2) { continue; } else {};
+    print($1, i);
+  };
+  for (j = 0; j < 5; j++) {
+    if (j == 1) { continue; } else {};
+    if (j == 3) { break; } else {};
+    print("for", j);
+  };
+}
+$1 == "stop" { exit 3; }
+END { print("done", NR); exit; }
+END { print("unreachable"); }
@@ -0,0 +1,2 @@
+{ total = total + $1; }
+END { print(total, NR, NF, $0); }
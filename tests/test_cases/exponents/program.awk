@@ -0,0 +1,11 @@
+# `^` groups to the right and binds tighter than a leading sign
+{
+    print $1 ^ 2, -$1 ^ 2, 2 ^ $1 ^ 2
+    print -$2, +$2, - -$2, !($2 + 0)
+    squares += $1 ^ 2
+}
+END {
+    print "squares", squares
+    x = 3; x ^= 2
+    print x, 2 ^ -2, 10 - -x
+}
@@ -0,0 +1,17 @@
+BEGIN { OFS = ":"; }
+{
+  $2 = "X";
+  print($0, NF);
+  $(NF + 2) = "end";
+  print($0, NF);
+  NF = 2;
+  print($0, NF, $3);
+  $0 = toupper($0);
+  print($1, NF);
+  NF++;
+  print($0);
+  gsub(/O/, "0");
+  sub(/0/, "o", $1);
+  print($0, $1);
+}
+END { print($0, NF); }
@@ -0,0 +1,19 @@
+NR == 1 { getline; print "skipped to", $0, NR, FNR }
+/^key/ {
+    getline value
+    print "value", value, $0, NR, NF
+}
+END {
+    file = "tests/test_cases/getline/input1.txt"
+    while ((getline line < file) > 0)
+        n++
+    print "lines", n, NR, line
+    print "reopened", close(file), (getline < file), $0, NR
+
+    "echo x y z" | getline
+    print $2, NF
+    print "closed", close("echo x y z"), close("echo x y z")
+
+    while (("printf 'p\\nq\\n'" | getline word) > 0)
+        print "word", word, $0
+}
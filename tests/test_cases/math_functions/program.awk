@@ -0,0 +1,7 @@
+{ print(int($1), int($2), sqrt($1 * $1), exp($2), log($1 * $1), sin($2), cos($2), atan2($1, $2)); }
+END {
+  srand(5);
+  print(srand(7), srand());
+  x = rand();
+  print((x >= 0), (x < 1), int(3), int("12.7xyz"));
+}
@@ -0,0 +1,11 @@
+$2 + 0 != 0 { print($1 / $2, $1 * $2, $1 * 1.5); }
+END {
+  print(0.1 * 3, 1e6 / 7, 100000 * 1.5, 1000000 * 1.5);
+  key[1 / 4] = "quarter";
+  CONVFMT = "%.2f";
+  key[1 / 3] = "third";
+  print(("0.25" in key), ("0.33" in key), key[0.25], (1 / 3 == "0.33"));
+  OFMT = "%.2e";
+  print(1 / 3, 8 / 2, -2.5);
+  print(1 / 3 == 0.3333333333333333);
+}
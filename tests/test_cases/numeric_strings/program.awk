@@ -0,0 +1,14 @@
+# Fields which look like numbers compare as numbers, but keep their original text
+$1 > 10 { print "big", $1 }
+$1 == 10 { print "ten", $1 }
+$1 < "5" { print "string order", $1 }
+!$1 { print "false", NR }
+{
+    n = split($0, parts)
+    if (parts[1] == $1 && parts[n] >= 0) {
+        print "split", parts[n]
+    }
+}
+END {
+    print (count == 0), (count == ""), (x < 1)
+}
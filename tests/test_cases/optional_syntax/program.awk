@@ -0,0 +1,17 @@
+# Items can leave out either the pattern or the action
+NR == 1
+/apple/
+
+$1 == "pear" { print "found", $2 }
+{
+    count = count + 1   # statements end at newlines
+    if (count == 2) {
+        print "second"
+    } else {
+        print("not second", count)
+    }
+}
+
+END {
+    print count; print "done"
+}
@@ -0,0 +1,9 @@
+# Every print ends with ORS, while printf only writes what it is given
+BEGIN { ORS = "|" }
+{ print NR, $1 }
+NR == 2 { ORS = "\r\n" }
+END {
+    ORS = ""
+    print "records:"
+    printf " %d\n", NR
+}
@@ -0,0 +1,13 @@
+BEGIN {
+    printf "" > "/tmp/rust_awk_redirection_test.txt"
+    close("/tmp/rust_awk_redirection_test.txt")
+}
+{ print NR, NF >> "/tmp/rust_awk_redirection_test.txt" }
+NF > 1 { print $2, $1 | "sort -r" }
+END {
+    close("sort -r")
+    printf "%d records\n", NR | "cat"
+    close("cat")
+    close("/tmp/rust_awk_redirection_test.txt")
+    printf "" | "cat /tmp/rust_awk_redirection_test.txt"
+}
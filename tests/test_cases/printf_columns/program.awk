@@ -0,0 +1,9 @@
+BEGIN { printf("%-10s|%5s|%8s\n", "name", "count", "price"); }
+{ printf "%-10s|%5d|%8.2f\n", $1, $2, $3; total = total + $2 * $3; }
+END {
+  printf("total: %e %g %G\n", total, total, 0.0000123);
+  printf("%x %X %#o %u %c%c %+d % d %%\n", 255, 255, 8, 42, 65, "bc", 5, 5);
+  printf("[%*d] [%-*d] [%.*s]\n", 6, NR, 6, NR, 3, "truncated");
+  printf("no newline");
+  printf("%s\n", sprintf("%03d", 7));
+}
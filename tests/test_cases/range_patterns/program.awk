@@ -0,0 +1,3 @@
+/BEGIN/, /END/ { print("block", NR, $0); }
+$2 == 3, $2 == 3 { print("single", NR); }
+NR == 7, 0 { print("open", NR); }
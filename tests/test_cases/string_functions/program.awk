@@ -0,0 +1,19 @@
+{
+  n = split($0, parts, ",");
+  print(NR, length, length($1), n, parts[n]);
+  print(substr($0, 3, 4), substr($0, 0), substr($0, length - 1), index($0, "o"));
+  line = $0;
+  count = gsub(/o/, "[&]", line);
+  print(count, line, toupper(line), tolower($1));
+  if (match($0, /[a-z]+a/)) { print("match", RSTART, RLENGTH, substr($0, RSTART, RLENGTH)); } else { print("no match", RSTART, RLENGTH); };
+}
+END {
+  s = "abc";
+  print(gsub(/x*/, "-", s), s);
+  s = "abbc";
+  print(gsub(/b*/, "-", s), s);
+  s = "hello";
+  print(sub(/l+/, "\\&\\\\&", s), s);
+  print(split("a:b:c", arr, /:/), arr[1], arr[3], length(arr));
+  print(sprintf("%-4s|", "ab"), index("abc", ""), length());
+}
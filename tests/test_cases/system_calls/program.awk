@@ -0,0 +1,12 @@
+BEGIN { print "before" }
+{
+    print "record", NR
+    status = system("echo from the shell")
+    print "status", status
+}
+END {
+    print "exit status", system("exit 3")
+    print "written" > "/tmp/rust_awk_system_test.txt"
+    system("cat /tmp/rust_awk_system_test.txt")
+    print "after"
+}
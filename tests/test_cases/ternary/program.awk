@@ -0,0 +1,10 @@
+# Only the chosen branch is evaluated, and the conditional groups to the right
+{
+    print (NF > 2 ? "long" : "short"), NR % 2 ? "odd" : "even"
+    size = NF == 0 ? "empty" : NF == 1 ? "one" : "many"
+    print size
+    NR > 1 ? taken++ : skipped++
+}
+END {
+    print taken, skipped + 0, (taken > 1 ? "several" : "few")
+}
@@ -0,0 +1,19 @@
+{
+    if (NF > 2) print "wide", NR
+    else if (NF == 0) print "empty", NR
+    else
+        print "narrow", NR
+
+    for (i = 1; i <= NF; i++)
+        if ($i ~ /^[0-9]+$/) total = total + $i
+
+    n = 0
+    while (n < NF) n++
+    do n--; while (n > 1)
+
+    {
+        words = words + NF
+    }
+    if (NR % 2) ; else { print "even", NR }
+}
+END { print total, words, n }